@@ -14,14 +14,29 @@ pub struct Forest<C: Context> {
     /// This effectively gives us way to track what depth
     /// and loop a table or strand was last followed.
     pub(crate) clock: TimeStamp,
+
+    /// Clauses that hold globally, in every environment, and are
+    /// considered alongside the program clauses for each domain goal.
+    pub(crate) initial_clauses: Vec<C::ProgramClause>,
 }
 
 impl<C: Context> Forest<C> {
     pub fn new(context: C) -> Self {
+        Self::with_initial_clauses(context, vec![])
+    }
+
+    /// Creates a forest seeded with global clauses, such as the
+    /// builtin impls of well-known traits, that never change between
+    /// invocations. These are in addition to the clauses returned by
+    /// `ContextOps::program_clauses`; as with those, the same clauses
+    /// must be in effect for as long as the forest's tables are
+    /// reused.
+    pub fn with_initial_clauses(context: C, initial_clauses: Vec<C::ProgramClause>) -> Self {
         Forest {
             context,
             tables: Tables::new(),
             clock: TimeStamp::default(),
+            initial_clauses,
         }
     }
 
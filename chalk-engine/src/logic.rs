@@ -259,7 +259,7 @@ impl<C: Context> Forest<C> {
             HhGoal::DomainGoal(domain_goal) => {
                 match context.program_clauses(&environment, &domain_goal, &mut infer) {
                     Ok(clauses) => {
-                        for clause in clauses {
+                        for clause in clauses.iter().chain(&self.initial_clauses) {
                             info!("program clause = {:#?}", clause);
                             let mut infer = infer.clone();
                             if let Ok(resolvent) = infer.resolvent_clause(
@@ -267,7 +267,7 @@ impl<C: Context> Forest<C> {
                                 &environment,
                                 &domain_goal,
                                 &subst,
-                                &clause,
+                                clause,
                             ) {
                                 info!("pushing initial strand with ex-clause: {:#?}", &resolvent,);
                                 let strand = Strand {
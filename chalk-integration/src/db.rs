@@ -2,6 +2,7 @@ use crate::error::ChalkError;
 use crate::lowering::LowerGoal;
 use crate::program::Program;
 use crate::query::{Lowering, LoweringDatabase};
+use crate::Identifier;
 use chalk_engine::forest::SubstitutionResult;
 use chalk_ir::interner::ChalkIr;
 use chalk_ir::tls;
@@ -17,10 +18,15 @@ use chalk_ir::StructId;
 use chalk_ir::TraitId;
 use chalk_ir::TypeName;
 use chalk_ir::UCanonical;
+use chalk_rust_ir::AssocConstId;
+use chalk_rust_ir::AssociatedConstDatum;
+use chalk_rust_ir::AssociatedConstValue;
+use chalk_rust_ir::AssociatedConstValueId;
 use chalk_rust_ir::AssociatedTyDatum;
 use chalk_rust_ir::AssociatedTyValue;
 use chalk_rust_ir::AssociatedTyValueId;
 use chalk_rust_ir::ImplDatum;
+use chalk_rust_ir::LangItem;
 use chalk_rust_ir::StructDatum;
 use chalk_rust_ir::TraitDatum;
 use chalk_rust_ir::WellKnownTrait;
@@ -89,6 +95,13 @@ impl RustIrDatabase<ChalkIr> for ChalkDatabase {
         self.program_ir().unwrap().associated_ty_data(ty)
     }
 
+    fn associated_const_data(
+        &self,
+        id: AssocConstId<ChalkIr>,
+    ) -> Arc<AssociatedConstDatum<ChalkIr>> {
+        self.program_ir().unwrap().associated_const_data(id)
+    }
+
     fn trait_datum(&self, id: TraitId<ChalkIr>) -> Arc<TraitDatum<ChalkIr>> {
         self.program_ir().unwrap().trait_datum(id)
     }
@@ -104,6 +117,13 @@ impl RustIrDatabase<ChalkIr> for ChalkDatabase {
         self.program_ir().unwrap().associated_ty_values[&id].clone()
     }
 
+    fn associated_const_value(
+        &self,
+        id: AssociatedConstValueId<ChalkIr>,
+    ) -> Arc<AssociatedConstValue<ChalkIr>> {
+        self.program_ir().unwrap().associated_const_value(id)
+    }
+
     fn struct_datum(&self, id: StructId<ChalkIr>) -> Arc<StructDatum<ChalkIr>> {
         self.program_ir().unwrap().struct_datum(id)
     }
@@ -138,12 +158,20 @@ impl RustIrDatabase<ChalkIr> for ChalkDatabase {
             .impl_provided_for(auto_trait_id, struct_id)
     }
 
+    fn trait_datum_by_name(&self, name: &Identifier) -> Option<Arc<TraitDatum<ChalkIr>>> {
+        self.program_ir().unwrap().trait_datum_by_name(name)
+    }
+
     fn well_known_trait_id(&self, well_known_trait: WellKnownTrait) -> TraitId<ChalkIr> {
         self.program_ir()
             .unwrap()
             .well_known_trait_id(well_known_trait)
     }
 
+    fn lang_item_struct(&self, lang_item: LangItem) -> Option<StructId<ChalkIr>> {
+        self.program_ir().unwrap().lang_item_struct(lang_item)
+    }
+
     fn interner(&self) -> &ChalkIr {
         &ChalkIr
     }
@@ -3,6 +3,8 @@ use chalk_parse::ast::{Identifier, Kind};
 use chalk_solve::coherence::CoherenceError;
 use chalk_solve::wf::WfError;
 
+pub use chalk_parse::ParseError;
+
 /// Wrapper type for the various errors that can occur during chalk
 /// processing.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -20,6 +22,14 @@ impl From<Box<dyn std::error::Error>> for ChalkError {
     }
 }
 
+impl From<ParseError> for ChalkError {
+    fn from(value: ParseError) -> Self {
+        ChalkError {
+            error_text: value.to_string(),
+        }
+    }
+}
+
 impl From<WfError<ChalkIr>> for ChalkError {
     fn from(value: WfError<ChalkIr>) -> Self {
         ChalkError {
@@ -62,9 +72,12 @@ pub enum RustIrError {
     AutoTraitAssociatedTypes(Identifier),
     AutoTraitParameters(Identifier),
     AutoTraitWhereClauses(Identifier),
+    MarkerTraitAssociatedTypes(Identifier),
+    InvalidMaybeBound(Identifier),
     InvalidFundamentalTypesParameters(Identifier),
     NegativeImplAssociatedValues(Identifier),
     MissingAssociatedType(Identifier),
+    MissingAssociatedConst(Identifier),
     IncorrectNumberOfTypeParameters {
         identifier: Identifier,
         expected: usize,
@@ -120,6 +133,16 @@ impl std::fmt::Display for RustIrError {
             RustIrError::AutoTraitWhereClauses(name) => {
                 write!(f, "auto trait `{}` cannot have where clauses", name)
             }
+            RustIrError::MarkerTraitAssociatedTypes(name) => {
+                write!(f, "marker trait `{}` cannot define associated types", name)
+            }
+            RustIrError::InvalidMaybeBound(name) => {
+                write!(
+                    f,
+                    "`?{}` is not a valid bound, only `?Sized` is supported",
+                    name
+                )
+            }
             RustIrError::InvalidFundamentalTypesParameters(name) => write!(
                 f,
                 "only a single parameter supported for fundamental type `{}`",
@@ -133,6 +156,9 @@ impl std::fmt::Display for RustIrError {
             RustIrError::MissingAssociatedType(name) => {
                 write!(f, "no associated type `{}` defined in trait", name)
             }
+            RustIrError::MissingAssociatedConst(name) => {
+                write!(f, "no associated const `{}` defined in trait", name)
+            }
             RustIrError::IncorrectNumberOfTypeParameters {
                 identifier,
                 expected,
@@ -7,6 +7,7 @@ extern crate chalk_macros;
 pub mod db;
 pub mod error;
 pub mod lowering;
+mod minimize;
 pub mod program;
 pub mod program_environment;
 pub mod query;
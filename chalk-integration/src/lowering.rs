@@ -6,7 +6,10 @@ use chalk_ir::{
 };
 use chalk_parse::ast::*;
 use chalk_rust_ir as rust_ir;
-use chalk_rust_ir::{Anonymize, AssociatedTyValueId, IntoWhereClauses, ToParameter};
+use chalk_rust_ir::{
+    Anonymize, AssocConstId, AssociatedConstValueId, AssociatedTyValueId, IntoWhereClauses,
+    ToParameter,
+};
 use lalrpop_intern::intern;
 use std::collections::BTreeMap;
 use std::sync::Arc;
@@ -22,6 +25,9 @@ type TraitKinds = BTreeMap<chalk_ir::TraitId<ChalkIr>, TypeKind>;
 type AssociatedTyLookups = BTreeMap<(chalk_ir::TraitId<ChalkIr>, Ident), AssociatedTyLookup>;
 type AssociatedTyValueIds =
     BTreeMap<(chalk_ir::ImplId<ChalkIr>, Ident), AssociatedTyValueId<ChalkIr>>;
+type AssociatedConstIds = BTreeMap<(chalk_ir::TraitId<ChalkIr>, Ident), AssocConstId<ChalkIr>>;
+type AssociatedConstValueIds =
+    BTreeMap<(chalk_ir::ImplId<ChalkIr>, Ident), AssociatedConstValueId<ChalkIr>>;
 type ParameterMap = BTreeMap<chalk_ir::ParameterKind<Ident>, BoundVar>;
 
 pub type LowerResult<T> = Result<T, RustIrError>;
@@ -194,15 +200,20 @@ impl LowerProgram for Program {
         // based just on its position:
         let raw_ids: Vec<_> = self.items.iter().map(|_| next_item_id()).collect();
 
-        // Create ids for associated type declarations and values
+        // Create ids for associated type and const declarations and values
         let mut associated_ty_lookups = BTreeMap::new();
         let mut associated_ty_value_ids = BTreeMap::new();
+        let mut associated_const_ids = BTreeMap::new();
+        let mut associated_const_value_ids = BTreeMap::new();
         for (item, &raw_id) in self.items.iter().zip(&raw_ids) {
             match item {
                 Item::TraitDefn(d) => {
                     if d.flags.auto && !d.assoc_ty_defns.is_empty() {
                         Err(RustIrError::AutoTraitAssociatedTypes(d.name))?;
                     }
+                    if d.flags.marker && !d.assoc_ty_defns.is_empty() {
+                        Err(RustIrError::MarkerTraitAssociatedTypes(d.name))?;
+                    }
                     for defn in &d.assoc_ty_defns {
                         let addl_parameter_kinds = defn.all_parameters();
                         let lookup = AssociatedTyLookup {
@@ -211,6 +222,10 @@ impl LowerProgram for Program {
                         };
                         associated_ty_lookups.insert((TraitId(raw_id), defn.name.str), lookup);
                     }
+                    for defn in &d.assoc_const_defns {
+                        let id = AssocConstId(next_item_id());
+                        associated_const_ids.insert((TraitId(raw_id), defn.name.str), id);
+                    }
                 }
 
                 Item::Impl(d) => {
@@ -218,6 +233,10 @@ impl LowerProgram for Program {
                         let atv_id = AssociatedTyValueId(next_item_id());
                         associated_ty_value_ids.insert((ImplId(raw_id), atv.name.str), atv_id);
                     }
+                    for acv in &d.assoc_const_values {
+                        let acv_id = AssociatedConstValueId(next_item_id());
+                        associated_const_value_ids.insert((ImplId(raw_id), acv.name.str), acv_id);
+                    }
                 }
 
                 _ => {}
@@ -250,9 +269,12 @@ impl LowerProgram for Program {
         let mut struct_data = BTreeMap::new();
         let mut trait_data = BTreeMap::new();
         let mut well_known_traits = BTreeMap::new();
+        let mut lang_items = rust_ir::LangItems::new();
         let mut impl_data = BTreeMap::new();
         let mut associated_ty_data = BTreeMap::new();
         let mut associated_ty_values = BTreeMap::new();
+        let mut associated_const_data = BTreeMap::new();
+        let mut associated_const_values = BTreeMap::new();
         let mut custom_clauses = Vec::new();
         for (item, &raw_id) in self.items.iter().zip(&raw_ids) {
             let empty_env = Env {
@@ -268,10 +290,15 @@ impl LowerProgram for Program {
                 Item::StructDefn(ref d) => {
                     let struct_id = StructId(raw_id);
                     struct_data.insert(struct_id, Arc::new(d.lower_struct(struct_id, &empty_env)?));
+
+                    if let Some(lang_item) = d.lang_item {
+                        lang_items.insert_struct(lang_item.lower(), struct_id);
+                    }
                 }
                 Item::TraitDefn(ref trait_defn) => {
                     let trait_id = TraitId(raw_id);
-                    let trait_datum = trait_defn.lower_trait(trait_id, &empty_env)?;
+                    let trait_datum =
+                        trait_defn.lower_trait(trait_id, &empty_env, &associated_const_ids)?;
 
                     if let Some(well_known) = trait_datum.well_known {
                         well_known_traits.insert(well_known, trait_id);
@@ -322,6 +349,24 @@ impl LowerProgram for Program {
                             }),
                         );
                     }
+
+                    for assoc_const_defn in &trait_defn.assoc_const_defns {
+                        let id = associated_const_ids[&(trait_id, assoc_const_defn.name.str)];
+                        let ty = empty_env.in_binders(trait_defn.all_parameters(), |env| {
+                            assoc_const_defn.ty.lower(env)
+                        })?;
+
+                        associated_const_data.insert(
+                            id,
+                            Arc::new(rust_ir::AssociatedConstDatum {
+                                trait_id,
+                                id,
+                                name: assoc_const_defn.name.str,
+                                ty,
+                                has_default: assoc_const_defn.has_default,
+                            }),
+                        );
+                    }
                 }
                 Item::Impl(ref impl_defn) => {
                     let impl_id = ImplId(raw_id);
@@ -329,6 +374,7 @@ impl LowerProgram for Program {
                         &empty_env,
                         impl_id,
                         &associated_ty_value_ids,
+                        &associated_const_value_ids,
                     )?);
                     impl_data.insert(impl_id, impl_datum.clone());
                     let trait_id = impl_datum.trait_id();
@@ -360,6 +406,26 @@ impl LowerProgram for Program {
                             }),
                         );
                     }
+
+                    for acv in &impl_defn.assoc_const_values {
+                        let acv_id = associated_const_value_ids[&(impl_id, acv.name.str)];
+                        let associated_const_id =
+                            match associated_const_ids.get(&(trait_id, acv.name.str)) {
+                                Some(&id) => id,
+                                None => Err(RustIrError::MissingAssociatedConst(acv.name))?,
+                            };
+                        let ty = empty_env
+                            .in_binders(impl_defn.all_parameters(), |env| acv.ty.lower(env))?;
+
+                        associated_const_values.insert(
+                            acv_id,
+                            Arc::new(rust_ir::AssociatedConstValue {
+                                impl_id,
+                                associated_const_id,
+                                ty,
+                            }),
+                        );
+                    }
                 }
                 Item::Clause(ref clause) => {
                     custom_clauses.extend(clause.lower_clause(&empty_env)?);
@@ -375,9 +441,12 @@ impl LowerProgram for Program {
             struct_data,
             trait_data,
             well_known_traits,
+            lang_items,
             impl_data,
             associated_ty_values,
             associated_ty_data,
+            associated_const_data,
+            associated_const_values,
             custom_clauses,
         };
 
@@ -625,11 +694,9 @@ impl LowerDomainGoal for DomainGoal {
     fn lower(&self, env: &Env) -> LowerResult<Vec<chalk_ir::DomainGoal<ChalkIr>>> {
         let interner = env.interner();
         let goals = match self {
-            DomainGoal::Holds { where_clause } => where_clause
-                .lower(env)?
-                .into_iter()
-                .casted(interner)
-                .collect(),
+            DomainGoal::Holds { where_clause } => {
+                where_clause.lower(env)?.into_iter().cast_all(interner)
+            }
             DomainGoal::Normalize { alias, ty } => {
                 vec![chalk_ir::DomainGoal::Normalize(chalk_ir::Normalize {
                     alias: alias.lower(env)?,
@@ -720,6 +787,11 @@ impl LowerStructDefn for StructDefn {
             })
         })?;
 
+        let mut unstable_features = vec![];
+        if self.flags.fundamental {
+            unstable_features.push(rust_ir::UnstableFeature::Fundamental);
+        }
+
         let flags = rust_ir::StructFlags {
             upstream: self.flags.upstream,
             fundamental: self.flags.fundamental,
@@ -729,6 +801,7 @@ impl LowerStructDefn for StructDefn {
             id: struct_id,
             binders,
             flags,
+            unstable_features,
         })
     }
 }
@@ -906,18 +979,33 @@ impl LowerImplType for ImplType {
 
 trait LowerTraitFlags {
     fn lower(&self) -> rust_ir::TraitFlags;
+    fn unstable_features(&self) -> Vec<rust_ir::UnstableFeature>;
 }
 
 impl LowerTraitFlags for TraitFlags {
     fn lower(&self) -> rust_ir::TraitFlags {
-        rust_ir::TraitFlags {
-            auto: self.auto,
-            marker: self.marker,
-            upstream: self.upstream,
-            fundamental: self.fundamental,
-            non_enumerable: self.non_enumerable,
-            coinductive: self.coinductive,
+        let mut flags = rust_ir::TraitFlags::default();
+        flags.set_auto(self.auto);
+        flags.set_marker(self.marker);
+        flags.set_upstream(self.upstream);
+        flags.set_fundamental(self.fundamental);
+        flags.set_non_enumerable(self.non_enumerable);
+        flags.set_coinductive(self.coinductive);
+        flags
+    }
+
+    fn unstable_features(&self) -> Vec<rust_ir::UnstableFeature> {
+        let mut unstable_features = vec![];
+        if self.auto {
+            unstable_features.push(rust_ir::UnstableFeature::AutoTraits);
+        }
+        if self.marker {
+            unstable_features.push(rust_ir::UnstableFeature::MarkerTraitAttr);
         }
+        if self.fundamental {
+            unstable_features.push(rust_ir::UnstableFeature::Fundamental);
+        }
+        unstable_features
     }
 }
 
@@ -941,7 +1029,7 @@ impl LowerAliasTy for AliasTy {
             Some(lookup) => lookup,
             None => Err(RustIrError::MissingAssociatedType(self.name))?,
         };
-        let mut args: Vec<_> = args
+        let args: Vec<_> = args
             .iter()
             .map(|a| a.lower(env))
             .collect::<LowerResult<_>>()?;
@@ -964,12 +1052,13 @@ impl LowerAliasTy for AliasTy {
             }
         }
 
-        args.extend(trait_substitution.iter(interner).cloned());
+        let substitution = chalk_ir::Substitution::from(interner, args)
+            .extend(trait_substitution.iter(interner).cloned(), interner);
 
-        Ok(chalk_ir::AliasTy {
+        Ok(chalk_ir::AliasTy::Projection(chalk_ir::ProjectionTy {
             associated_ty_id: lookup.id,
-            substitution: chalk_ir::Substitution::from(interner, args),
-        })
+            substitution,
+        }))
     }
 }
 
@@ -1125,6 +1214,7 @@ trait LowerImpl {
         empty_env: &Env,
         impl_id: ImplId<ChalkIr>,
         associated_ty_value_ids: &AssociatedTyValueIds,
+        associated_const_value_ids: &AssociatedConstValueIds,
     ) -> LowerResult<rust_ir::ImplDatum<ChalkIr>>;
 }
 
@@ -1134,6 +1224,7 @@ impl LowerImpl for Impl {
         empty_env: &Env,
         impl_id: ImplId<ChalkIr>,
         associated_ty_value_ids: &AssociatedTyValueIds,
+        associated_const_value_ids: &AssociatedConstValueIds,
     ) -> LowerResult<rust_ir::ImplDatum<ChalkIr>> {
         debug_heading!("LowerImpl::lower_impl(impl_id={:?})", impl_id);
 
@@ -1142,7 +1233,9 @@ impl LowerImpl for Impl {
             let trait_ref = self.trait_ref.lower(env)?;
             debug!("trait_ref = {:?}", trait_ref);
 
-            if !polarity.is_positive() && !self.assoc_ty_values.is_empty() {
+            if !polarity.is_positive()
+                && (!self.assoc_ty_values.is_empty() || !self.assoc_const_values.is_empty())
+            {
                 Err(RustIrError::NegativeImplAssociatedValues(
                     self.trait_ref.trait_name,
                 ))?;
@@ -1167,11 +1260,18 @@ impl LowerImpl for Impl {
 
         debug!("associated_ty_value_ids = {:?}", associated_ty_value_ids);
 
+        let associated_const_value_ids = self
+            .assoc_const_values
+            .iter()
+            .map(|acv| associated_const_value_ids[&(impl_id, acv.name.str)])
+            .collect();
+
         Ok(rust_ir::ImplDatum {
             polarity,
             binders: binders,
             impl_type: self.impl_type.lower(),
             associated_ty_value_ids,
+            associated_const_value_ids,
         })
     }
 }
@@ -1221,6 +1321,7 @@ trait LowerTrait {
         &self,
         trait_id: chalk_ir::TraitId<ChalkIr>,
         env: &Env,
+        associated_const_ids: &AssociatedConstIds,
     ) -> LowerResult<rust_ir::TraitDatum<ChalkIr>>;
 }
 
@@ -1229,6 +1330,7 @@ impl LowerTrait for TraitDefn {
         &self,
         trait_id: chalk_ir::TraitId<ChalkIr>,
         env: &Env,
+        associated_const_ids: &AssociatedConstIds,
     ) -> LowerResult<rust_ir::TraitDatum<ChalkIr>> {
         let all_parameters = self.all_parameters();
         let all_parameters_len = all_parameters.len();
@@ -1253,12 +1355,27 @@ impl LowerTrait for TraitDefn {
             .map(|defn| env.associated_ty_lookups[&(trait_id, defn.name.str)].id)
             .collect();
 
+        let associated_const_ids: Vec<_> = self
+            .assoc_const_defns
+            .iter()
+            .map(|defn| associated_const_ids[&(trait_id, defn.name.str)])
+            .collect();
+
+        let self_sized = match self.maybe_bound {
+            None => true,
+            Some(bound) if bound.str == intern("Sized") => false,
+            Some(bound) => Err(RustIrError::InvalidMaybeBound(bound))?,
+        };
+
         let trait_datum = rust_ir::TraitDatum {
             id: trait_id,
             binders: binders,
             flags: self.flags.lower(),
             associated_ty_ids,
+            associated_const_ids,
             well_known: self.well_known.map(|t| t.lower()),
+            self_sized,
+            unstable_features: self.flags.unstable_features(),
         };
 
         debug!("trait_datum={:?}", trait_datum);
@@ -1373,6 +1490,19 @@ impl LowerWellKnownTrait for WellKnownTrait {
     fn lower(&self) -> rust_ir::WellKnownTrait {
         match self {
             Self::SizedTrait => rust_ir::WellKnownTrait::SizedTrait,
+            Self::DropTrait => rust_ir::WellKnownTrait::DropTrait,
+        }
+    }
+}
+
+trait LowerLangItem {
+    fn lower(&self) -> rust_ir::LangItem;
+}
+
+impl LowerLangItem for LangItem {
+    fn lower(&self) -> rust_ir::LangItem {
+        match self {
+            Self::PhantomData => rust_ir::LangItem::PhantomData,
         }
     }
 }
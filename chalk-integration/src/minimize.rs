@@ -0,0 +1,275 @@
+use crate::program::Program;
+use chalk_engine::fallible::Fallible;
+use chalk_ir::fold::{Fold, Folder, SuperFold};
+use chalk_ir::interner::ChalkIr;
+use chalk_ir::{
+    AliasTy, AssocTypeId, DebruijnIndex, ImplId, QuantifiedWhereClause, StructId, TraitId, Ty,
+    TyData, TypeName, WhereClause,
+};
+use chalk_rust_ir::{InlineBound, LangItems, QuantifiedInlineBound};
+use std::collections::{BTreeMap, BTreeSet};
+
+impl Program {
+    /// Extracts the impl `impl_id`, together with every struct, trait
+    /// and associated type that it references (directly or
+    /// transitively), into a standalone program. Useful for reducing
+    /// a bug report down to the items that matter.
+    ///
+    /// The result is meant to pass the same checks as the original
+    /// program (see `query::check_program`): the well-known traits are
+    /// always kept, and so are all impls of the traits that are kept,
+    /// since proving the impl well-formed may need any of them (e.g.
+    /// an impl of a supertrait).
+    pub fn extract_minimal_program(&self, impl_id: ImplId<ChalkIr>) -> Program {
+        let mut collector = ItemCollector {
+            interner: &ChalkIr,
+            structs: BTreeSet::new(),
+            traits: BTreeSet::new(),
+            associated_tys: BTreeSet::new(),
+            impls: BTreeSet::new(),
+            pending: vec![],
+        };
+
+        collector.add_impl(impl_id);
+        for &trait_id in self.well_known_traits.values() {
+            collector.add_trait(trait_id);
+        }
+
+        // Each item is only queued the first time it is seen, so
+        // cycles between items (e.g. a struct that contains itself)
+        // terminate.
+        while let Some(item) = collector.pending.pop() {
+            match item {
+                Item::Struct(id) => {
+                    let bound = &self.struct_data[&id].binders.value;
+                    collector.visit(&bound.fields);
+                    collector.visit_where_clauses(&bound.where_clauses);
+                }
+                Item::Trait(id) => {
+                    let trait_datum = &self.trait_data[&id];
+                    collector.visit_where_clauses(&trait_datum.binders.value.where_clauses);
+                    for &associated_ty_id in &trait_datum.associated_ty_ids {
+                        collector.add_associated_ty(associated_ty_id);
+                    }
+                    for associated_const_id in &trait_datum.associated_const_ids {
+                        collector.visit(&self.associated_const_data[associated_const_id].ty);
+                    }
+                    for (&impl_id, impl_datum) in &self.impl_data {
+                        if impl_datum.trait_id() == id {
+                            collector.add_impl(impl_id);
+                        }
+                    }
+                }
+                Item::AssociatedTy(id) => {
+                    let associated_ty_datum = &self.associated_ty_data[&id];
+                    let bound = &associated_ty_datum.binders.value;
+                    collector.add_trait(associated_ty_datum.trait_id);
+                    collector.visit_inline_bounds(&bound.bounds);
+                    collector.visit_where_clauses(&bound.where_clauses);
+                }
+                Item::Impl(id) => {
+                    let impl_datum = &self.impl_data[&id];
+                    let impl_bound = &impl_datum.binders.value;
+                    collector.add_trait(impl_bound.trait_ref.trait_id);
+                    collector.visit(&impl_bound.trait_ref);
+                    collector.visit_where_clauses(&impl_bound.where_clauses);
+                    for value_id in &impl_datum.associated_ty_value_ids {
+                        collector.visit(&self.associated_ty_values[value_id].value);
+                    }
+                    for value_id in &impl_datum.associated_const_value_ids {
+                        collector.visit(&self.associated_const_values[value_id].ty);
+                    }
+                }
+            }
+        }
+
+        let ItemCollector {
+            structs,
+            traits,
+            associated_tys,
+            impls,
+            ..
+        } = collector;
+
+        let mut lang_items = LangItems::new();
+        for (lang_item, struct_id) in self.lang_items.iter() {
+            if structs.contains(&struct_id) {
+                lang_items.insert_struct(lang_item, struct_id);
+            }
+        }
+
+        let impl_data = filter_keys(&self.impl_data, &impls);
+        let associated_ty_values = impl_data
+            .values()
+            .flat_map(|impl_datum| &impl_datum.associated_ty_value_ids)
+            .map(|id| (*id, self.associated_ty_values[id].clone()))
+            .collect();
+        let associated_const_values = impl_data
+            .values()
+            .flat_map(|impl_datum| &impl_datum.associated_const_value_ids)
+            .map(|id| (*id, self.associated_const_values[id].clone()))
+            .collect();
+
+        Program {
+            struct_ids: filter_values(&self.struct_ids, &structs),
+            struct_kinds: filter_keys(&self.struct_kinds, &structs),
+            trait_ids: filter_values(&self.trait_ids, &traits),
+            trait_kinds: filter_keys(&self.trait_kinds, &traits),
+            struct_data: filter_keys(&self.struct_data, &structs),
+            impl_data,
+            associated_ty_values,
+            trait_data: filter_keys(&self.trait_data, &traits),
+            well_known_traits: self.well_known_traits.clone(),
+            lang_items,
+            associated_ty_data: filter_keys(&self.associated_ty_data, &associated_tys),
+            associated_const_data: self
+                .associated_const_data
+                .iter()
+                .filter(|(_, datum)| traits.contains(&datum.trait_id))
+                .map(|(&id, datum)| (id, datum.clone()))
+                .collect(),
+            associated_const_values,
+            custom_clauses: vec![],
+        }
+    }
+}
+
+fn filter_keys<K: Ord + Clone, V: Clone>(
+    map: &BTreeMap<K, V>,
+    keep: &BTreeSet<K>,
+) -> BTreeMap<K, V> {
+    map.iter()
+        .filter(|(k, _)| keep.contains(k))
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect()
+}
+
+fn filter_values<K: Ord + Clone, V: Ord + Clone>(
+    map: &BTreeMap<K, V>,
+    keep: &BTreeSet<V>,
+) -> BTreeMap<K, V> {
+    map.iter()
+        .filter(|(_, v)| keep.contains(v))
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect()
+}
+
+enum Item {
+    Struct(StructId<ChalkIr>),
+    Trait(TraitId<ChalkIr>),
+    AssociatedTy(AssocTypeId<ChalkIr>),
+    Impl(ImplId<ChalkIr>),
+}
+
+/// Records the items referenced by the values it folds, queueing
+/// each newly seen item so that its own references can be followed.
+struct ItemCollector<'i> {
+    interner: &'i ChalkIr,
+    structs: BTreeSet<StructId<ChalkIr>>,
+    traits: BTreeSet<TraitId<ChalkIr>>,
+    associated_tys: BTreeSet<AssocTypeId<ChalkIr>>,
+    impls: BTreeSet<ImplId<ChalkIr>>,
+    pending: Vec<Item>,
+}
+
+impl ItemCollector<'_> {
+    fn add_struct(&mut self, id: StructId<ChalkIr>) {
+        if self.structs.insert(id) {
+            self.pending.push(Item::Struct(id));
+        }
+    }
+
+    fn add_trait(&mut self, id: TraitId<ChalkIr>) {
+        if self.traits.insert(id) {
+            self.pending.push(Item::Trait(id));
+        }
+    }
+
+    fn add_associated_ty(&mut self, id: AssocTypeId<ChalkIr>) {
+        if self.associated_tys.insert(id) {
+            self.pending.push(Item::AssociatedTy(id));
+        }
+    }
+
+    /// Opaque types have no datum in the program, so only
+    /// projections name an item to keep.
+    fn add_alias(&mut self, alias: &AliasTy<ChalkIr>) {
+        match alias {
+            AliasTy::Projection(projection) => self.add_associated_ty(projection.associated_ty_id),
+            AliasTy::Opaque(_) => {}
+        }
+    }
+
+    fn add_impl(&mut self, id: ImplId<ChalkIr>) {
+        if self.impls.insert(id) {
+            self.pending.push(Item::Impl(id));
+        }
+    }
+
+    /// Records the types in `value`; trait references have to be
+    /// recorded separately, since folding never reaches a `TraitId`.
+    fn visit<T: Fold<ChalkIr>>(&mut self, value: &T) {
+        value
+            .fold_with(self, DebruijnIndex::INNERMOST)
+            .expect("collecting items cannot fail");
+    }
+
+    fn add_where_clause(&mut self, where_clause: &WhereClause<ChalkIr>) {
+        match where_clause {
+            WhereClause::Implemented(trait_ref) => self.add_trait(trait_ref.trait_id),
+            WhereClause::AliasEq(alias_eq) => self.add_alias(&alias_eq.alias),
+        }
+    }
+
+    fn visit_where_clauses(&mut self, where_clauses: &[QuantifiedWhereClause<ChalkIr>]) {
+        for where_clause in where_clauses {
+            self.add_where_clause(&where_clause.value);
+            self.visit(where_clause);
+        }
+    }
+
+    fn visit_inline_bounds(&mut self, bounds: &[QuantifiedInlineBound<ChalkIr>]) {
+        for bound in bounds {
+            match &bound.value {
+                InlineBound::TraitBound(trait_bound) => self.add_trait(trait_bound.trait_id),
+                InlineBound::AliasEqBound(alias_eq_bound) => {
+                    self.add_trait(alias_eq_bound.trait_bound.trait_id);
+                    self.add_associated_ty(alias_eq_bound.associated_ty_id);
+                }
+            }
+            self.visit(bound);
+        }
+    }
+}
+
+impl<'i> Folder<'i, ChalkIr> for ItemCollector<'i> {
+    fn as_dyn(&mut self) -> &mut dyn Folder<'i, ChalkIr> {
+        self
+    }
+
+    fn fold_ty(&mut self, ty: &Ty<ChalkIr>, outer_binder: DebruijnIndex) -> Fallible<Ty<ChalkIr>> {
+        match ty.data(self.interner) {
+            TyData::Apply(apply) => match apply.name {
+                TypeName::Struct(id) => self.add_struct(id),
+                TypeName::AssociatedType(id) => self.add_associated_ty(id),
+                TypeName::Error => {}
+            },
+            TyData::Alias(alias) => self.add_alias(alias),
+            TyData::Dyn(dyn_ty) => {
+                for where_clause in dyn_ty.bounds.value.iter(self.interner) {
+                    self.add_where_clause(&where_clause.value);
+                }
+            }
+            _ => {}
+        }
+        ty.super_fold_with(self.as_dyn(), outer_binder)
+    }
+
+    fn interner(&self) -> &'i ChalkIr {
+        self.interner
+    }
+
+    fn target_interner(&self) -> &'i ChalkIr {
+        self.interner()
+    }
+}
@@ -4,13 +4,14 @@ use chalk_ir::debug::Angle;
 use chalk_ir::interner::ChalkIr;
 use chalk_ir::tls;
 use chalk_ir::{
-    debug::SeparatorTraitRef, AliasTy, ApplicationTy, AssocTypeId, Goal, Goals, ImplId, Lifetime,
-    Parameter, ProgramClause, ProgramClauseImplication, ProgramClauses, StructId, Substitution,
+    debug::SeparatorTraitRef, ApplicationTy, AssocTypeId, Goal, Goals, ImplId, Lifetime, Parameter,
+    ProgramClause, ProgramClauseImplication, ProgramClauses, ProjectionTy, StructId, Substitution,
     TraitId, Ty, TyData, TypeName,
 };
 use chalk_rust_ir::{
-    AssociatedTyDatum, AssociatedTyValue, AssociatedTyValueId, ImplDatum, ImplType, StructDatum,
-    TraitDatum, WellKnownTrait,
+    AssocConstId, AssociatedConstDatum, AssociatedConstValue, AssociatedConstValueId,
+    AssociatedTyDatum, AssociatedTyValue, AssociatedTyValueId, ImplDatum, LangItem, LangItems,
+    StructDatum, TraitDatum, WellKnownTrait,
 };
 use chalk_solve::split::Split;
 use chalk_solve::RustIrDatabase;
@@ -48,9 +49,19 @@ pub struct Program {
     /// For each trait lang item
     pub well_known_traits: BTreeMap<WellKnownTrait, TraitId<ChalkIr>>,
 
+    /// For each struct lang item
+    pub lang_items: LangItems<ChalkIr>,
+
     /// For each associated ty declaration `type Foo` found in a trait:
     pub associated_ty_data: BTreeMap<AssocTypeId<ChalkIr>, Arc<AssociatedTyDatum<ChalkIr>>>,
 
+    /// For each associated const declaration `const FOO: Ty` found in a trait:
+    pub associated_const_data: BTreeMap<AssocConstId<ChalkIr>, Arc<AssociatedConstDatum<ChalkIr>>>,
+
+    /// For each associated const value `const FOO: Ty = _` found in an impl:
+    pub associated_const_values:
+        BTreeMap<AssociatedConstValueId<ChalkIr>, Arc<AssociatedConstValue<ChalkIr>>>,
+
     /// For each user-specified clause
     pub custom_clauses: Vec<ProgramClause<ChalkIr>>,
 }
@@ -60,10 +71,193 @@ impl Program {
     pub(crate) fn local_impl_ids(&self) -> Vec<ImplId<ChalkIr>> {
         self.impl_data
             .iter()
-            .filter(|(_, impl_datum)| impl_datum.impl_type == ImplType::Local)
+            .filter(|(_, impl_datum)| impl_datum.impl_type.is_local())
             .map(|(&impl_id, _)| impl_id)
             .collect()
     }
+
+    /// Checks that every id referenced by the program's data refers
+    /// to an item that the program contains, returning an error for
+    /// each reference that doesn't. A program produced by lowering
+    /// should always validate; this is meant for catching bugs in the
+    /// lowering itself.
+    ///
+    /// Only the ids stored directly in the maps and datums are
+    /// checked, not the ids appearing within types and where clauses.
+    pub fn validate(&self) -> Vec<ValidationError> {
+        let mut errors = vec![];
+        let mut check = |item: ItemId, reference: ItemId, exists: bool| {
+            if !exists {
+                errors.push(ValidationError { item, reference });
+            }
+        };
+
+        for (&name, &id) in &self.struct_ids {
+            check(
+                ItemId::Name(name),
+                ItemId::Struct(id),
+                self.struct_data.contains_key(&id),
+            );
+        }
+        for (&name, &id) in &self.trait_ids {
+            check(
+                ItemId::Name(name),
+                ItemId::Trait(id),
+                self.trait_data.contains_key(&id),
+            );
+        }
+        for (&id, struct_datum) in &self.struct_data {
+            check(
+                ItemId::Struct(id),
+                ItemId::Struct(struct_datum.id),
+                struct_datum.id == id,
+            );
+        }
+        for (&id, trait_datum) in &self.trait_data {
+            check(
+                ItemId::Trait(id),
+                ItemId::Trait(trait_datum.id),
+                trait_datum.id == id,
+            );
+            for &assoc_id in &trait_datum.associated_ty_ids {
+                check(
+                    ItemId::Trait(id),
+                    ItemId::AssociatedTy(assoc_id),
+                    self.associated_ty_data.contains_key(&assoc_id),
+                );
+            }
+            for &assoc_id in &trait_datum.associated_const_ids {
+                check(
+                    ItemId::Trait(id),
+                    ItemId::AssociatedConst(assoc_id),
+                    self.associated_const_data.contains_key(&assoc_id),
+                );
+            }
+        }
+        for (&id, associated_const_datum) in &self.associated_const_data {
+            check(
+                ItemId::AssociatedConst(id),
+                ItemId::AssociatedConst(associated_const_datum.id),
+                associated_const_datum.id == id,
+            );
+            check(
+                ItemId::AssociatedConst(id),
+                ItemId::Trait(associated_const_datum.trait_id),
+                self.trait_data
+                    .contains_key(&associated_const_datum.trait_id),
+            );
+        }
+        for (&id, associated_ty_datum) in &self.associated_ty_data {
+            check(
+                ItemId::AssociatedTy(id),
+                ItemId::AssociatedTy(associated_ty_datum.id),
+                associated_ty_datum.id == id,
+            );
+            check(
+                ItemId::AssociatedTy(id),
+                ItemId::Trait(associated_ty_datum.trait_id),
+                self.trait_data.contains_key(&associated_ty_datum.trait_id),
+            );
+        }
+        for (&id, impl_datum) in &self.impl_data {
+            check(
+                ItemId::Impl(id),
+                ItemId::Trait(impl_datum.trait_id()),
+                self.trait_data.contains_key(&impl_datum.trait_id()),
+            );
+            for &value_id in &impl_datum.associated_ty_value_ids {
+                check(
+                    ItemId::Impl(id),
+                    ItemId::AssociatedTyValue(value_id),
+                    self.associated_ty_values.contains_key(&value_id),
+                );
+            }
+            for &value_id in &impl_datum.associated_const_value_ids {
+                check(
+                    ItemId::Impl(id),
+                    ItemId::AssociatedConstValue(value_id),
+                    self.associated_const_values.contains_key(&value_id),
+                );
+            }
+        }
+        for (&id, associated_ty_value) in &self.associated_ty_values {
+            check(
+                ItemId::AssociatedTyValue(id),
+                ItemId::Impl(associated_ty_value.impl_id),
+                self.impl_data.contains_key(&associated_ty_value.impl_id),
+            );
+            check(
+                ItemId::AssociatedTyValue(id),
+                ItemId::AssociatedTy(associated_ty_value.associated_ty_id),
+                self.associated_ty_data
+                    .contains_key(&associated_ty_value.associated_ty_id),
+            );
+        }
+        for (&id, associated_const_value) in &self.associated_const_values {
+            check(
+                ItemId::AssociatedConstValue(id),
+                ItemId::Impl(associated_const_value.impl_id),
+                self.impl_data.contains_key(&associated_const_value.impl_id),
+            );
+            check(
+                ItemId::AssociatedConstValue(id),
+                ItemId::AssociatedConst(associated_const_value.associated_const_id),
+                self.associated_const_data
+                    .contains_key(&associated_const_value.associated_const_id),
+            );
+        }
+        for (&well_known, &id) in &self.well_known_traits {
+            check(
+                ItemId::WellKnownTrait(well_known),
+                ItemId::Trait(id),
+                self.trait_data.contains_key(&id),
+            );
+        }
+        for (lang_item, id) in self.lang_items.iter() {
+            check(
+                ItemId::LangItem(lang_item),
+                ItemId::Struct(id),
+                self.struct_data.contains_key(&id),
+            );
+        }
+
+        errors
+    }
+}
+
+/// Identifies an item of a `Program`, or an entry in one of its maps
+/// that is keyed by something other than an id (see `ValidationError`).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ItemId {
+    Name(Identifier),
+    Struct(StructId<ChalkIr>),
+    Trait(TraitId<ChalkIr>),
+    AssociatedTy(AssocTypeId<ChalkIr>),
+    Impl(ImplId<ChalkIr>),
+    AssociatedTyValue(AssociatedTyValueId<ChalkIr>),
+    AssociatedConst(AssocConstId<ChalkIr>),
+    AssociatedConstValue(AssociatedConstValueId<ChalkIr>),
+    WellKnownTrait(WellKnownTrait),
+    LangItem(LangItem),
+}
+
+/// A broken reference found by `Program::validate`: `item` refers to
+/// `reference`, which the program does not contain (or, for a datum
+/// whose own id differs from its key, `reference` is that own id).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidationError {
+    pub item: ItemId,
+    pub reference: ItemId,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} has a broken reference to {:?}",
+            self.item, self.reference
+        )
+    }
 }
 
 impl tls::DebugContext for Program {
@@ -109,12 +303,26 @@ impl tls::DebugContext for Program {
         }
     }
 
-    fn debug_alias(
+    fn debug_assoc_type_name(
         &self,
-        alias_ty: &AliasTy<ChalkIr>,
+        assoc_type_id: AssocTypeId<ChalkIr>,
         fmt: &mut fmt::Formatter<'_>,
     ) -> Result<(), fmt::Error> {
-        let (associated_ty_data, trait_params, other_params) = self.split_projection(alias_ty);
+        if let Some(k) = self.associated_ty_data.get(&assoc_type_id) {
+            write!(fmt, "{}", k.name)
+        } else {
+            fmt.debug_struct("InvalidItemId")
+                .field("index", &assoc_type_id.0)
+                .finish()
+        }
+    }
+
+    fn debug_projection_ty(
+        &self,
+        projection_ty: &ProjectionTy<ChalkIr>,
+        fmt: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        let (associated_ty_data, trait_params, other_params) = self.split_projection(projection_ty);
         write!(
             fmt,
             "<{:?} as {:?}{:?}>::{}{:?}",
@@ -240,6 +448,13 @@ impl RustIrDatabase<ChalkIr> for Program {
         self.associated_ty_data[&ty].clone()
     }
 
+    fn associated_const_data(
+        &self,
+        id: AssocConstId<ChalkIr>,
+    ) -> Arc<AssociatedConstDatum<ChalkIr>> {
+        self.associated_const_data[&id].clone()
+    }
+
     fn trait_datum(&self, id: TraitId<ChalkIr>) -> Arc<TraitDatum<ChalkIr>> {
         self.trait_data[&id].clone()
     }
@@ -255,6 +470,13 @@ impl RustIrDatabase<ChalkIr> for Program {
         self.associated_ty_values[&id].clone()
     }
 
+    fn associated_const_value(
+        &self,
+        id: AssociatedConstValueId<ChalkIr>,
+    ) -> Arc<AssociatedConstValue<ChalkIr>> {
+        self.associated_const_values[&id].clone()
+    }
+
     fn struct_datum(&self, id: StructId<ChalkIr>) -> Arc<StructDatum<ChalkIr>> {
         self.struct_data[&id].clone()
     }
@@ -293,7 +515,7 @@ impl RustIrDatabase<ChalkIr> for Program {
         self.impl_data
             .iter()
             .filter(|(_, impl_datum)| {
-                impl_datum.trait_id() == trait_id && impl_datum.impl_type == ImplType::Local
+                impl_datum.trait_id() == trait_id && impl_datum.impl_type.is_local()
             })
             .map(|(&impl_id, _)| impl_id)
             .collect()
@@ -321,6 +543,12 @@ impl RustIrDatabase<ChalkIr> for Program {
         })
     }
 
+    fn trait_datum_by_name(&self, name: &Identifier) -> Option<Arc<TraitDatum<ChalkIr>>> {
+        self.trait_ids
+            .get(name)
+            .map(|id| self.trait_data[id].clone())
+    }
+
     fn well_known_trait_id(&self, well_known_trait: WellKnownTrait) -> TraitId<ChalkIr> {
         *self
             .well_known_traits
@@ -328,6 +556,10 @@ impl RustIrDatabase<ChalkIr> for Program {
             .unwrap_or_else(|| panic!("No lang item found for {:?}", well_known_trait))
     }
 
+    fn lang_item_struct(&self, lang_item: LangItem) -> Option<StructId<ChalkIr>> {
+        self.lang_items.struct_id(lang_item)
+    }
+
     fn interner(&self) -> &ChalkIr {
         &ChalkIr
     }
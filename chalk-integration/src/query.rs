@@ -62,58 +62,95 @@ fn program_ir(db: &impl LoweringDatabase) -> Result<Arc<Program>, ChalkError> {
 fn orphan_check(db: &impl LoweringDatabase) -> Result<(), ChalkError> {
     let program = db.program_ir()?;
 
-    tls::set_current_program(&program, || -> Result<(), ChalkError> {
-        let local_impls = program.local_impl_ids();
-        for impl_id in local_impls {
-            orphan::perform_orphan_check(db, db.solver_choice(), impl_id)?;
-        }
-        Ok(())
+    tls::set_current_program(&program, || {
+        orphan_check_impls(db, &program, db.solver_choice())
     })
 }
 
+fn orphan_check_impls(
+    db: &dyn RustIrDatabase<ChalkIr>,
+    program: &Program,
+    solver_choice: SolverChoice,
+) -> Result<(), ChalkError> {
+    for impl_id in program.local_impl_ids() {
+        orphan::perform_orphan_check(db, solver_choice, impl_id)?;
+    }
+    Ok(())
+}
+
 fn coherence(
     db: &impl LoweringDatabase,
 ) -> Result<BTreeMap<TraitId<ChalkIr>, Arc<SpecializationPriorities<ChalkIr>>>, ChalkError> {
     let program = db.program_ir()?;
 
-    let priorities_map = tls::set_current_program(&program, || -> Result<_, ChalkError> {
-        let priorities_map: Result<BTreeMap<_, _>, ChalkError> = program
-            .trait_data
-            .keys()
-            .map(|&trait_id| {
-                let solver = CoherenceSolver::new(db, db.solver_choice(), trait_id);
-                let priorities = solver.specialization_priorities()?;
-                Ok((trait_id, priorities))
-            })
-            .collect();
-        let priorities_map = priorities_map?;
-        Ok(priorities_map)
+    let priorities_map = tls::set_current_program(&program, || {
+        specialization_priorities(db, &program, db.solver_choice())
     });
     let () = db.orphan_check()?;
     priorities_map
 }
 
+fn specialization_priorities(
+    db: &dyn RustIrDatabase<ChalkIr>,
+    program: &Program,
+    solver_choice: SolverChoice,
+) -> Result<BTreeMap<TraitId<ChalkIr>, Arc<SpecializationPriorities<ChalkIr>>>, ChalkError> {
+    program
+        .trait_data
+        .keys()
+        .map(|&trait_id| {
+            let solver = CoherenceSolver::new(db, solver_choice, trait_id);
+            let priorities = solver.specialization_priorities()?;
+            Ok((trait_id, priorities))
+        })
+        .collect()
+}
+
 fn checked_program(db: &impl LoweringDatabase) -> Result<Arc<Program>, ChalkError> {
     let program = db.program_ir()?;
 
     db.coherence()?;
 
-    let () = tls::set_current_program(&program, || -> Result<(), ChalkError> {
-        let solver = wf::WfSolver::new(db, db.solver_choice());
-        for &id in program.struct_data.keys() {
-            solver.verify_struct_decl(id)?;
-        }
-
-        for &impl_id in program.impl_data.keys() {
-            solver.verify_trait_impl(impl_id)?;
-        }
-
-        Ok(())
+    let () = tls::set_current_program(&program, || {
+        verify_well_formed(db, &program, db.solver_choice())
     })?;
 
     Ok(program)
 }
 
+fn verify_well_formed(
+    db: &dyn RustIrDatabase<ChalkIr>,
+    program: &Program,
+    solver_choice: SolverChoice,
+) -> Result<(), ChalkError> {
+    let solver = wf::WfSolver::new(db, solver_choice);
+    for &id in program.struct_data.keys() {
+        solver.verify_struct_decl(id)?;
+    }
+
+    for &impl_id in program.impl_data.keys() {
+        solver.verify_trait_impl(impl_id)?;
+    }
+
+    Ok(())
+}
+
+/// Performs the coherence, orphan and WF checks of `checked_program`
+/// on a program that was not lowered from source text, such as one
+/// produced by `Program::extract_minimal_program`. The solver's
+/// queries are answered by `program` itself.
+pub fn check_program(
+    program: &Arc<Program>,
+    solver_choice: SolverChoice,
+) -> Result<(), ChalkError> {
+    tls::set_current_program(program, || {
+        let db: &dyn RustIrDatabase<ChalkIr> = &**program;
+        specialization_priorities(db, program, solver_choice)?;
+        orphan_check_impls(db, program, solver_choice)?;
+        verify_well_formed(db, program, solver_choice)
+    })
+}
+
 fn environment(db: &impl LoweringDatabase) -> Result<Arc<ProgramEnvironment>, ChalkError> {
     let program = db.program_ir()?;
 
@@ -1,4 +1,5 @@
 use crate::*;
+use std::iter::FromIterator;
 use std::marker::PhantomData;
 
 /// The `Cast` trait is used to make annoying upcasts between
@@ -286,7 +287,7 @@ where
     U: HasInterner,
 {
     fn cast_to(self, interner: &U::Interner) -> Vec<U> {
-        self.into_iter().casted(interner).collect()
+        self.into_iter().cast_all(interner)
     }
 }
 
@@ -344,6 +345,26 @@ pub trait Caster: Iterator + Sized {
             _cast: PhantomData,
         }
     }
+
+    /// Casts each element and collects the results into a `Vec`.
+    fn cast_all<U>(self, interner: &U::Interner) -> Vec<U>
+    where
+        Self::Item: CastTo<U>,
+        U: HasInterner,
+    {
+        self.casted(interner).collect()
+    }
+
+    /// Casts each element and collects the results into any
+    /// collection `C`.
+    fn cast_all_into<U, C>(self, interner: &U::Interner) -> C
+    where
+        Self::Item: CastTo<U>,
+        U: HasInterner,
+        C: FromIterator<U>,
+    {
+        self.casted(interner).collect()
+    }
 }
 
 impl<I> Caster for I where I: Iterator {}
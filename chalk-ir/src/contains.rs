@@ -0,0 +1,73 @@
+//! Read-only checks for whether a term contains some kind of subterm.
+
+use crate::interner::Interner;
+use crate::*;
+
+/// The kind of subterm that a `ContainsChecker` looks for.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub(crate) enum ContainsKind {
+    Alias,
+    Placeholder,
+    InferenceVar,
+}
+
+/// Walks a type looking for a subterm of the given kind. Unlike a
+/// folder, it only borrows the term and stops at the first match, so
+/// nothing is rebuilt.
+pub(crate) struct ContainsChecker<'i, I: Interner> {
+    interner: &'i I,
+    kind: ContainsKind,
+}
+
+impl<'i, I: Interner> ContainsChecker<'i, I> {
+    pub(crate) fn new(interner: &'i I, kind: ContainsKind) -> Self {
+        ContainsChecker { interner, kind }
+    }
+
+    pub(crate) fn ty(&self, ty: &Ty<I>) -> bool {
+        match ty.data(self.interner) {
+            TyData::Apply(apply) => self.substitution(&apply.substitution),
+            TyData::Placeholder(_) => self.kind == ContainsKind::Placeholder,
+            TyData::Dyn(dyn_ty) => dyn_ty
+                .bounds
+                .value
+                .iter(self.interner)
+                .any(|bound| self.where_clause(&bound.value)),
+            TyData::Alias(alias) => {
+                self.kind == ContainsKind::Alias || self.substitution(alias.substitution())
+            }
+            TyData::Function(fn_ty) => self.substitution(&fn_ty.substitution),
+            TyData::BoundVar(_) => false,
+            TyData::InferenceVar(_) => self.kind == ContainsKind::InferenceVar,
+        }
+    }
+
+    fn lifetime(&self, lifetime: &Lifetime<I>) -> bool {
+        match lifetime.data(self.interner) {
+            LifetimeData::Placeholder(_) => self.kind == ContainsKind::Placeholder,
+            LifetimeData::InferenceVar(_) => self.kind == ContainsKind::InferenceVar,
+            LifetimeData::BoundVar(_) | LifetimeData::Phantom(..) => false,
+        }
+    }
+
+    fn substitution(&self, substitution: &Substitution<I>) -> bool {
+        substitution
+            .iter(self.interner)
+            .any(|parameter| match parameter.data(self.interner) {
+                ParameterKind::Ty(ty) => self.ty(ty),
+                ParameterKind::Lifetime(lifetime) => self.lifetime(lifetime),
+            })
+    }
+
+    /// The alias in an `AliasEq` bound names the associated type
+    /// being constrained rather than a type to normalize, so only its
+    /// parameters are searched.
+    fn where_clause(&self, where_clause: &WhereClause<I>) -> bool {
+        match where_clause {
+            WhereClause::Implemented(trait_ref) => self.substitution(&trait_ref.substitution),
+            WhereClause::AliasEq(alias_eq) => {
+                self.substitution(alias_eq.alias.substitution()) || self.ty(&alias_eq.ty)
+            }
+        }
+    }
+}
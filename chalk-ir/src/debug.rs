@@ -85,7 +85,26 @@ impl<I: Interner> Debug for SeparatorTraitRef<'_, I> {
 
 impl<I: Interner> Debug for AliasTy<I> {
     fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
-        I::debug_alias(self, fmt).unwrap_or_else(|| write!(fmt, "AliasTy(?)"))
+        match self {
+            AliasTy::Projection(projection) => write!(fmt, "{:?}", projection),
+            AliasTy::Opaque(opaque) => write!(fmt, "{:?}", opaque),
+        }
+    }
+}
+
+impl<I: Interner> Debug for ProjectionTy<I> {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
+        I::debug_projection_ty(self, fmt).unwrap_or_else(|| write!(fmt, "ProjectionTy(?)"))
+    }
+}
+
+impl<I: Interner> Debug for OpaqueTy<I> {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
+        let OpaqueTy {
+            opaque_ty_id,
+            substitution,
+        } = self;
+        write!(fmt, "{:?}{:?}", opaque_ty_id, substitution)
     }
 }
 
@@ -482,6 +501,8 @@ impl<I: Interner> Debug for WellFormed<I> {
         match self {
             WellFormed::Trait(t) => write!(fmt, "WellFormed({:?})", t.with_colon()),
             WellFormed::Ty(t) => write!(fmt, "WellFormed({:?})", t),
+            WellFormed::Impl(id) => write!(fmt, "WellFormed(impl {:?})", id),
+            WellFormed::Struct(id) => write!(fmt, "WellFormed(struct {:?})", id),
         }
     }
 }
@@ -6,10 +6,19 @@ use std::fmt::Debug;
 
 mod binder_impls;
 mod boring_impls;
+mod closed;
+mod free_lifetimes;
+mod max_universe;
+mod null;
 pub mod shift;
 mod subst;
 
+pub(crate) use self::closed::ClosedChecker;
+pub(crate) use self::free_lifetimes::FreeLifetimeCollector;
+pub(crate) use self::max_universe::MaxUniverseCollector;
+pub use self::null::NullFolder;
 pub use self::subst::Subst;
+pub(crate) use self::subst::SubstBoundVar;
 
 /// A "folder" is a transformer that can be used to make a copy of
 /// some term -- that is, some bit of IR, such as a `Goal` -- with
@@ -0,0 +1,62 @@
+use super::*;
+
+/// A folder that fails as soon as it encounters an inference variable
+/// or a bound variable that is not bound within the value being
+/// folded. Used to test whether a value is "closed".
+pub(crate) struct ClosedChecker<'i, I: Interner> {
+    interner: &'i I,
+}
+
+impl<I: Interner> ClosedChecker<'_, I> {
+    pub(crate) fn is_closed<T: Fold<I, I>>(interner: &I, value: &T) -> bool {
+        value
+            .fold_with(&mut ClosedChecker { interner }, DebruijnIndex::INNERMOST)
+            .is_ok()
+    }
+}
+
+impl<'i, I: Interner> Folder<'i, I> for ClosedChecker<'i, I> {
+    fn as_dyn(&mut self) -> &mut dyn Folder<'i, I> {
+        self
+    }
+
+    fn fold_free_var_ty(
+        &mut self,
+        _bound_var: BoundVar,
+        _outer_binder: DebruijnIndex,
+    ) -> Fallible<Ty<I>> {
+        Err(NoSolution)
+    }
+
+    fn fold_free_var_lifetime(
+        &mut self,
+        _bound_var: BoundVar,
+        _outer_binder: DebruijnIndex,
+    ) -> Fallible<Lifetime<I>> {
+        Err(NoSolution)
+    }
+
+    fn fold_inference_ty(
+        &mut self,
+        _var: InferenceVar,
+        _outer_binder: DebruijnIndex,
+    ) -> Fallible<Ty<I>> {
+        Err(NoSolution)
+    }
+
+    fn fold_inference_lifetime(
+        &mut self,
+        _var: InferenceVar,
+        _outer_binder: DebruijnIndex,
+    ) -> Fallible<Lifetime<I>> {
+        Err(NoSolution)
+    }
+
+    fn interner(&self) -> &'i I {
+        self.interner
+    }
+
+    fn target_interner(&self) -> &'i I {
+        self.interner()
+    }
+}
@@ -0,0 +1,50 @@
+use super::*;
+
+/// A folder that records every lifetime bound variable that is free
+/// in the value being folded, i.e. not bound within the value itself.
+pub(crate) struct FreeLifetimeCollector<'i, I: Interner> {
+    interner: &'i I,
+    lifetimes: Vec<Lifetime<I>>,
+}
+
+impl<I: Interner> FreeLifetimeCollector<'_, I> {
+    /// Returns the free lifetime variables of `value`, in order of
+    /// first occurrence, expressed relative to the top of `value`.
+    pub(crate) fn collect<T: Fold<I, I>>(interner: &I, value: &T) -> Vec<Lifetime<I>> {
+        let mut collector = FreeLifetimeCollector {
+            interner,
+            lifetimes: vec![],
+        };
+        value
+            .fold_with(&mut collector, DebruijnIndex::INNERMOST)
+            .expect("collecting lifetimes cannot fail");
+        collector.lifetimes
+    }
+}
+
+impl<'i, I: Interner> Folder<'i, I> for FreeLifetimeCollector<'i, I> {
+    fn as_dyn(&mut self) -> &mut dyn Folder<'i, I> {
+        self
+    }
+
+    fn fold_free_var_lifetime(
+        &mut self,
+        bound_var: BoundVar,
+        outer_binder: DebruijnIndex,
+    ) -> Fallible<Lifetime<I>> {
+        let lifetime = LifetimeData::<I>::BoundVar(bound_var).intern(self.interner);
+        if !self.lifetimes.contains(&lifetime) {
+            self.lifetimes.push(lifetime);
+        }
+        let bound_var = bound_var.shifted_in_from(outer_binder);
+        Ok(LifetimeData::<I>::BoundVar(bound_var).intern(self.interner))
+    }
+
+    fn interner(&self) -> &'i I {
+        self.interner
+    }
+
+    fn target_interner(&self) -> &'i I {
+        self.interner()
+    }
+}
@@ -0,0 +1,56 @@
+use super::*;
+use std::cmp::max;
+
+/// A folder that records the largest universe of any placeholder
+/// type or lifetime in the value being folded.
+pub(crate) struct MaxUniverseCollector<'i, I: Interner> {
+    interner: &'i I,
+    max_universe: UniverseIndex,
+}
+
+impl<I: Interner> MaxUniverseCollector<'_, I> {
+    /// Returns the maximum universe of the placeholders in `value`,
+    /// or the root universe if there are none.
+    pub(crate) fn collect<T: Fold<I, I>>(interner: &I, value: &T) -> UniverseIndex {
+        let mut collector = MaxUniverseCollector {
+            interner,
+            max_universe: UniverseIndex::ROOT,
+        };
+        value
+            .fold_with(&mut collector, DebruijnIndex::INNERMOST)
+            .expect("collecting universes cannot fail");
+        collector.max_universe
+    }
+}
+
+impl<'i, I: Interner> Folder<'i, I> for MaxUniverseCollector<'i, I> {
+    fn as_dyn(&mut self) -> &mut dyn Folder<'i, I> {
+        self
+    }
+
+    fn fold_free_placeholder_ty(
+        &mut self,
+        universe: PlaceholderIndex,
+        _outer_binder: DebruijnIndex,
+    ) -> Fallible<Ty<I>> {
+        self.max_universe = max(self.max_universe, universe.ui);
+        Ok(universe.to_ty(self.interner))
+    }
+
+    fn fold_free_placeholder_lifetime(
+        &mut self,
+        universe: PlaceholderIndex,
+        _outer_binder: DebruijnIndex,
+    ) -> Fallible<Lifetime<I>> {
+        self.max_universe = max(self.max_universe, universe.ui);
+        Ok(universe.to_lifetime(self.interner))
+    }
+
+    fn interner(&self) -> &'i I {
+        self.interner
+    }
+
+    fn target_interner(&self) -> &'i I {
+        self.interner()
+    }
+}
@@ -0,0 +1,34 @@
+use super::*;
+
+/// A folder that leaves everything unchanged: it relies entirely on
+/// the default `Folder` methods, so folding a value with it simply
+/// rebuilds the value. Useful for checking that a `Fold` impl is
+/// well-behaved, since the result should always equal the input:
+///
+/// ```rust,ignore
+/// let folded = value.fold_with(&mut NullFolder::new(interner), DebruijnIndex::INNERMOST)?;
+/// assert_eq!(folded, value);
+/// ```
+pub struct NullFolder<'i, I: Interner> {
+    interner: &'i I,
+}
+
+impl<'i, I: Interner> NullFolder<'i, I> {
+    pub fn new(interner: &'i I) -> Self {
+        NullFolder { interner }
+    }
+}
+
+impl<'i, I: Interner> Folder<'i, I> for NullFolder<'i, I> {
+    fn as_dyn(&mut self) -> &mut dyn Folder<'i, I> {
+        self
+    }
+
+    fn interner(&self) -> &'i I {
+        self.interner
+    }
+
+    fn target_interner(&self) -> &'i I {
+        self.interner()
+    }
+}
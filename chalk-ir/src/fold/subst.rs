@@ -95,3 +95,63 @@ impl<'i, I: Interner> Folder<'i, I> for Subst<'_, 'i, I> {
         self.interner()
     }
 }
+
+/// Replaces a single bound type variable with a given type, leaving
+/// all other bound variables untouched. Unlike `Subst`, this does not
+/// eliminate a binder, so no other indices are shifted.
+pub(crate) struct SubstBoundVar<'s, 'i, I: Interner> {
+    /// The variable to replace, relative to the top of the folded term.
+    bound_var: BoundVar,
+    replacement: &'s Ty<I>,
+    interner: &'i I,
+}
+
+impl<I: Interner> SubstBoundVar<'_, '_, I> {
+    pub(crate) fn apply<T: Fold<I, I>>(
+        interner: &I,
+        bound_var: BoundVar,
+        replacement: &Ty<I>,
+        value: &T,
+    ) -> T::Result {
+        value
+            .fold_with(
+                &mut SubstBoundVar {
+                    bound_var,
+                    replacement,
+                    interner,
+                },
+                DebruijnIndex::INNERMOST,
+            )
+            .unwrap()
+    }
+}
+
+impl<'i, I: Interner> Folder<'i, I> for SubstBoundVar<'_, 'i, I> {
+    fn as_dyn(&mut self) -> &mut dyn Folder<'i, I> {
+        self
+    }
+
+    fn fold_free_var_ty(
+        &mut self,
+        bound_var: BoundVar,
+        outer_binder: DebruijnIndex,
+    ) -> Fallible<Ty<I>> {
+        if bound_var == self.bound_var {
+            Ok(self
+                .replacement
+                .shifted_in_from(self.interner(), outer_binder))
+        } else {
+            Ok(bound_var
+                .shifted_in_from(outer_binder)
+                .to_ty(self.interner()))
+        }
+    }
+
+    fn interner(&self) -> &'i I {
+        self.interner
+    }
+
+    fn target_interner(&self) -> &'i I {
+        self.interner()
+    }
+}
@@ -0,0 +1,30 @@
+//! Helpers for building quantified goals.
+
+use crate::fold::shift::Shift;
+use crate::interner::Interner;
+use crate::{Goal, ParameterKind, QuantifierKind};
+
+/// Creates `forall<binders> { goal }`.
+///
+/// `goal` is given relative to the scope outside the new binder, so
+/// its free bound variables are shifted in by one level to keep
+/// referring to the same binders. `goal` itself cannot refer to the
+/// new variables; use `Goal::quantify` for a body that does.
+pub fn for_all<I: Interner>(
+    binders: Vec<ParameterKind<()>>,
+    goal: Goal<I>,
+    interner: &I,
+) -> Goal<I> {
+    goal.shifted_in(interner)
+        .quantify(interner, QuantifierKind::ForAll, binders)
+}
+
+/// Creates `exists<binders> { goal }`; see `for_all`.
+pub fn exists<I: Interner>(
+    binders: Vec<ParameterKind<()>>,
+    goal: Goal<I>,
+    interner: &I,
+) -> Goal<I> {
+    goal.shifted_in(interner)
+        .quantify(interner, QuantifierKind::Exists, binders)
+}
@@ -1,4 +1,4 @@
-use crate::AliasTy;
+use crate::zip::TermEqZipper;
 use crate::ApplicationTy;
 use crate::AssocTypeId;
 use crate::Goal;
@@ -12,6 +12,7 @@ use crate::ProgramClause;
 use crate::ProgramClauseData;
 use crate::ProgramClauseImplication;
 use crate::ProgramClauses;
+use crate::ProjectionTy;
 use crate::QuantifiedWhereClause;
 use crate::QuantifiedWhereClauses;
 use crate::SeparatorTraitRef;
@@ -22,8 +23,9 @@ use crate::Ty;
 use crate::TyData;
 use chalk_engine::context::Context;
 use chalk_engine::ExClause;
+use std::cmp::Ordering;
 use std::fmt::{self, Debug};
-use std::hash::Hash;
+use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
 use std::sync::Arc;
 
@@ -170,7 +172,21 @@ pub trait Interner: Debug + Copy + Eq + Ord + Hash {
         None
     }
 
-    /// Prints the debug representation of an alias. To get good
+    /// Prints just the name of an associated type (e.g. `Item`),
+    /// without the trait it belongs to. Like `debug_assoc_type_id`,
+    /// this requires inspecting TLS to give good results.
+    ///
+    /// Returns `None` to fallback to the default debug output (e.g.,
+    /// if no info about current program is available from TLS).
+    #[allow(unused_variables)]
+    fn debug_assoc_type_name(
+        type_id: AssocTypeId<Self>,
+        fmt: &mut fmt::Formatter<'_>,
+    ) -> Option<fmt::Result> {
+        None
+    }
+
+    /// Prints the debug representation of a projection. To get good
     /// results, this requires inspecting TLS, and is difficult to
     /// code without reference to a specific interner (and hence
     /// fully known types).
@@ -178,7 +194,10 @@ pub trait Interner: Debug + Copy + Eq + Ord + Hash {
     /// Returns `None` to fallback to the default debug output (e.g.,
     /// if no info about current program is available from TLS).
     #[allow(unused_variables)]
-    fn debug_alias(alias: &AliasTy<Self>, fmt: &mut fmt::Formatter<'_>) -> Option<fmt::Result> {
+    fn debug_projection_ty(
+        projection: &ProjectionTy<Self>,
+        fmt: &mut fmt::Formatter<'_>,
+    ) -> Option<fmt::Result> {
         None
     }
 
@@ -361,6 +380,40 @@ pub trait Interner: Debug + Copy + Eq + Ord + Hash {
     /// Lookup the `TyData` from an interned type.
     fn ty_data<'a>(&self, ty: &'a Self::InternedType) -> &'a TyData<Self>;
 
+    /// Compares two interned types for equality. By default this uses
+    /// the `PartialEq` impl of `InternedType`; interners that hashcons
+    /// their types can override it to compare pointers instead.
+    fn interned_ty_eq(a: &Self::InternedType, b: &Self::InternedType) -> bool {
+        a == b
+    }
+
+    /// Hashes an interned type. This must agree with
+    /// `interned_ty_eq`: an interner that compares pointers should
+    /// hash the pointer as well.
+    fn interned_ty_hash<H: Hasher>(ty: &Self::InternedType, state: &mut H) {
+        ty.hash(state)
+    }
+
+    /// Orders two interned types. Only available when `InternedType`
+    /// is itself `Ord`; like `interned_ty_hash`, an override must
+    /// agree with `interned_ty_eq`.
+    fn interned_ty_cmp(a: &Self::InternedType, b: &Self::InternedType) -> Ordering
+    where
+        Self::InternedType: Ord,
+    {
+        a.cmp(b)
+    }
+
+    /// Compares two interned types by their `TyData`, regardless of
+    /// how `interned_ty_eq` is implemented. The comparison recurses
+    /// into the types nested within, so they are compared by their
+    /// data as well.
+    fn term_eq(&self, a: &Self::InternedType, b: &Self::InternedType) -> bool {
+        TermEqZipper::new(self)
+            .zip_ty_data(self.ty_data(a), self.ty_data(b))
+            .is_ok()
+    }
+
     /// Create an "interned" lifetime from `lifetime`. This is not
     /// normally invoked directly; instead, you invoke
     /// `LifetimeData::intern` (which will ultimately call this
@@ -535,11 +588,18 @@ mod default {
             tls::with_current_program(|prog| Some(prog?.debug_assoc_type_id(id, fmt)))
         }
 
-        fn debug_alias(
-            alias: &AliasTy<ChalkIr>,
+        fn debug_assoc_type_name(
+            id: AssocTypeId<ChalkIr>,
+            fmt: &mut fmt::Formatter<'_>,
+        ) -> Option<fmt::Result> {
+            tls::with_current_program(|prog| Some(prog?.debug_assoc_type_name(id, fmt)))
+        }
+
+        fn debug_projection_ty(
+            projection: &ProjectionTy<ChalkIr>,
             fmt: &mut fmt::Formatter<'_>,
         ) -> Option<fmt::Result> {
-            tls::with_current_program(|prog| Some(prog?.debug_alias(alias, fmt)))
+            tls::with_current_program(|prog| Some(prog?.debug_projection_ty(projection, fmt)))
         }
 
         fn debug_ty(ty: &Ty<ChalkIr>, fmt: &mut fmt::Formatter<'_>) -> Option<fmt::Result> {
@@ -5,6 +5,8 @@ use crate::fold::shift::Shift;
 use crate::fold::{Fold, Folder, Subst, SuperFold};
 use chalk_derive::{Fold, HasInterner};
 use chalk_engine::fallible::*;
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
 use std::iter;
 use std::marker::PhantomData;
 
@@ -39,8 +41,12 @@ pub mod cast;
 pub mod interner;
 use interner::{HasInterner, Interner, TargetInterner};
 
+mod contains;
+use contains::{ContainsChecker, ContainsKind};
+
 pub mod could_match;
 pub mod debug;
+pub mod goals;
 #[cfg(any(test, feature = "default-interner"))]
 pub mod tls;
 
@@ -169,13 +175,50 @@ pub struct ClauseId<I: Interner>(pub I::DefId);
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct AssocTypeId<I: Interner>(pub I::DefId);
 
-impl_debugs!(ImplId, ClauseId);
+/// Identifier for an opaque type, e.g. the `impl Iterator` returned by
+/// `fn foo() -> impl Iterator`.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct OpaqueTyId<I: Interner>(pub I::DefId);
 
-#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord, HasInterner)]
+impl_debugs!(ImplId, ClauseId, OpaqueTyId);
+
+#[derive(Clone, HasInterner)]
 pub struct Ty<I: Interner> {
     interned: I::InternedType,
 }
 
+impl<I: Interner> PartialEq for Ty<I> {
+    fn eq(&self, other: &Self) -> bool {
+        I::interned_ty_eq(&self.interned, &other.interned)
+    }
+}
+
+impl<I: Interner> Eq for Ty<I> {}
+
+impl<I: Interner> Hash for Ty<I> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        I::interned_ty_hash(&self.interned, state)
+    }
+}
+
+impl<I: Interner> PartialOrd for Ty<I>
+where
+    I::InternedType: Ord,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<I: Interner> Ord for Ty<I>
+where
+    I::InternedType: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        I::interned_ty_cmp(&self.interned, &other.interned)
+    }
+}
+
 impl<I: Interner> Ty<I> {
     pub fn new(interner: &I, data: impl CastTo<TyData<I>>) -> Self {
         Ty {
@@ -229,6 +272,84 @@ impl<I: Interner> Ty<I> {
         }
     }
 
+    /// If this is a projection like `<T as Trait>::Item`, returns
+    /// `Some` with the projection, else `None`. Opaque types are
+    /// aliases too, but are not projections.
+    pub fn as_projection(&self, interner: &I) -> Option<&ProjectionTy<I>> {
+        if let TyData::Alias(AliasTy::Projection(projection)) = self.data(interner) {
+            Some(projection)
+        } else {
+            None
+        }
+    }
+
+    /// If this is an opaque type like `impl Trait`, returns `Some`
+    /// with it, else `None`.
+    pub fn as_opaque_ty(&self, interner: &I) -> Option<&OpaqueTy<I>> {
+        if let TyData::Alias(AliasTy::Opaque(opaque)) = self.data(interner) {
+            Some(opaque)
+        } else {
+            None
+        }
+    }
+
+    /// Replaces the bound variable `^debruijn.index` with
+    /// `replacement`, leaving all other bound variables as they are.
+    /// `replacement` is given relative to the top of `self` and is
+    /// shifted in as we descend through binders.
+    pub fn substitute_bound_var(
+        self,
+        index: usize,
+        debruijn: DebruijnIndex,
+        replacement: &Ty<I>,
+        interner: &I,
+    ) -> Ty<I> {
+        fold::SubstBoundVar::apply(interner, BoundVar::new(debruijn, index), replacement, &self)
+    }
+
+    /// If this is a placeholder, returns `Some` with the universe it
+    /// was created in, else `None`.
+    pub fn universe_of_placeholder(&self, interner: &I) -> Option<UniverseIndex> {
+        if let TyData::Placeholder(placeholder) = self.data(interner) {
+            Some(placeholder.ui)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the largest universe of any placeholder type or
+    /// lifetime within this type, or the root universe if there are
+    /// none. A type can only be named from universes that can see
+    /// this one.
+    pub fn max_universe(&self, interner: &I) -> UniverseIndex {
+        fold::MaxUniverseCollector::collect(interner, self)
+    }
+
+    /// True if this type contains an alias (projection) type anywhere
+    /// within it. A type without aliases needs no normalization.
+    pub fn contains_alias(&self, interner: &I) -> bool {
+        ContainsChecker::new(interner, ContainsKind::Alias).ty(self)
+    }
+
+    /// True if this type contains a placeholder type or lifetime.
+    pub fn contains_placeholder(&self, interner: &I) -> bool {
+        ContainsChecker::new(interner, ContainsKind::Placeholder).ty(self)
+    }
+
+    /// True if this type contains an inference type or lifetime
+    /// variable.
+    pub fn contains_inference_var(&self, interner: &I) -> bool {
+        ContainsChecker::new(interner, ContainsKind::InferenceVar).ty(self)
+    }
+
+    /// Returns the lifetime variables that are bound outside of this
+    /// type, without duplicates; lifetimes bound by a `for<'a>` within
+    /// the type are skipped. Useful for finding which lifetimes a
+    /// type depends on.
+    pub fn free_lifetime_variables(&self, interner: &I) -> Vec<Lifetime<I>> {
+        fold::FreeLifetimeCollector::collect(interner, self)
+    }
+
     /// True if this type contains "bound" types/lifetimes, and hence
     /// needs to be shifted across binders. This is a very inefficient
     /// check, intended only for debug assertions, because I am lazy.
@@ -722,11 +843,11 @@ impl<T> ParameterKind<T> {
 
 impl<T, L> ParameterKind<T, L> {
     pub fn assert_ty_ref(&self) -> &T {
-        self.as_ref().ty().unwrap()
+        self.as_ref().unwrap_ty()
     }
 
     pub fn assert_lifetime_ref(&self) -> &L {
-        self.as_ref().lifetime().unwrap()
+        self.as_ref().unwrap_lifetime()
     }
 
     pub fn as_ref(&self) -> ParameterKind<&T, &L> {
@@ -743,6 +864,10 @@ impl<T, L> ParameterKind<T, L> {
         }
     }
 
+    pub fn is_lifetime(&self) -> bool {
+        !self.is_ty()
+    }
+
     pub fn ty(self) -> Option<T> {
         match self {
             ParameterKind::Ty(t) => Some(t),
@@ -756,6 +881,22 @@ impl<T, L> ParameterKind<T, L> {
             _ => None,
         }
     }
+
+    /// Returns the type, panicking if this is a lifetime.
+    pub fn unwrap_ty(self) -> T {
+        match self {
+            ParameterKind::Ty(t) => t,
+            ParameterKind::Lifetime(_) => panic!("expected a type parameter, found a lifetime"),
+        }
+    }
+
+    /// Returns the lifetime, panicking if this is a type.
+    pub fn unwrap_lifetime(self) -> L {
+        match self {
+            ParameterKind::Lifetime(l) => l,
+            ParameterKind::Ty(_) => panic!("expected a lifetime parameter, found a type"),
+        }
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord, HasInterner)]
@@ -776,11 +917,11 @@ impl<I: Interner> Parameter<I> {
     }
 
     pub fn assert_ty_ref(&self, interner: &I) -> &Ty<I> {
-        self.as_ref(interner).ty().unwrap()
+        self.as_ref(interner).unwrap_ty()
     }
 
     pub fn assert_lifetime_ref(&self, interner: &I) -> &Lifetime<I> {
-        self.as_ref(interner).lifetime().unwrap()
+        self.as_ref(interner).unwrap_lifetime()
     }
 
     pub fn as_ref(&self, interner: &I) -> ParameterKind<&Ty<I>, &Lifetime<I>> {
@@ -821,16 +962,57 @@ impl<I: Interner> ParameterData<I> {
     }
 }
 
+/// An alias type: either an associated type projection like
+/// `<T as Iterator>::Item`, or an opaque type like the `impl Trait`
+/// returned by a function.
 #[derive(Clone, PartialEq, Eq, Hash, Fold, HasInterner)]
-pub struct AliasTy<I: Interner> {
-    pub associated_ty_id: AssocTypeId<I>,
-    pub substitution: Substitution<I>,
+pub enum AliasTy<I: Interner> {
+    Projection(ProjectionTy<I>),
+    Opaque(OpaqueTy<I>),
 }
 
 impl<I: Interner> AliasTy<I> {
     pub fn intern(self, interner: &I) -> Ty<I> {
         Ty::new(interner, self)
     }
+
+    pub fn substitution(&self) -> &Substitution<I> {
+        match self {
+            AliasTy::Projection(projection) => &projection.substitution,
+            AliasTy::Opaque(opaque) => &opaque.substitution,
+        }
+    }
+
+    /// True if the alias's substitution mentions no inference
+    /// variables and no free bound variables, so that the alias can
+    /// be normalized independently of any surrounding context.
+    pub fn is_closed_alias(&self, interner: &I) -> bool {
+        fold::ClosedChecker::is_closed(interner, self.substitution())
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Fold, HasInterner)]
+pub struct ProjectionTy<I: Interner> {
+    pub associated_ty_id: AssocTypeId<I>,
+    pub substitution: Substitution<I>,
+}
+
+impl<I: Interner> ProjectionTy<I> {
+    pub fn intern(self, interner: &I) -> Ty<I> {
+        Ty::new(interner, AliasTy::Projection(self))
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Fold, HasInterner)]
+pub struct OpaqueTy<I: Interner> {
+    pub opaque_ty_id: OpaqueTyId<I>,
+    pub substitution: Substitution<I>,
+}
+
+impl<I: Interner> OpaqueTy<I> {
+    pub fn intern(self, interner: &I) -> Ty<I> {
+        Ty::new(interner, AliasTy::Opaque(self))
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, Hash, Fold, HasInterner)]
@@ -847,8 +1029,22 @@ impl<I: Interner> TraitRef<I> {
             .cloned()
     }
 
+    /// The `Self` type, which is always the first parameter.
     pub fn self_type_parameter(&self, interner: &I) -> Ty<I> {
-        self.type_parameters(interner).next().unwrap()
+        self.substitution
+            .at(interner, 0)
+            .assert_ty_ref(interner)
+            .clone()
+    }
+
+    /// True if `self` and `other` name the same trait with the same
+    /// parameters, apart from the self type (parameter 0). For
+    /// example `T: Foo<u32>` and `U: Foo<u32>` agree, `T: Foo<i32>`
+    /// does not.
+    pub fn eq_ignoring_self_type(&self, other: &TraitRef<I>, interner: &I) -> bool {
+        self.trait_id == other.trait_id
+            && self.substitution.parameters(interner)[1..]
+                == other.substitution.parameters(interner)[1..]
     }
 
     pub fn from_env(self) -> FromEnv<I> {
@@ -895,6 +1091,17 @@ pub enum WellFormed<I: Interner> {
     ///
     /// then we have the following rule: `WellFormedTy(Set<K>) :- Implemented(K: Hash)`.
     Ty(Ty<I>),
+
+    /// A predicate which is true if an impl is well-formed, i.e. its
+    /// header and associated type values satisfy the trait's
+    /// requirements under the impl's where clauses.
+    Impl(ImplId<I>),
+
+    /// A predicate which is true if a struct declaration is well-formed,
+    /// i.e. its field types are well-formed under its where clauses.
+    /// Unlike `Ty`, this is about the declaration itself rather than
+    /// some particular instantiation of it.
+    Struct(StructId<I>),
 }
 
 #[derive(Clone, PartialEq, Eq, Hash, Fold, HasInterner)]
@@ -1013,6 +1220,19 @@ impl<I: Interner> WhereClause<I> {
             wc => wc.cast(interner),
         }
     }
+
+    /// The type that the where clause is about, e.g. `T` in `T: Trait`.
+    ///
+    /// Returns `None` for `AliasEq`: the self type of a projection is
+    /// the first of the *trait* parameters, which come after the
+    /// associated type's own parameters in the alias substitution, so
+    /// it can't be located without the associated type's datum.
+    pub fn self_ty(&self, interner: &I) -> Option<Ty<I>> {
+        match self {
+            WhereClause::Implemented(trait_ref) => Some(trait_ref.self_type_parameter(interner)),
+            WhereClause::AliasEq(_) => None,
+        }
+    }
 }
 
 impl<I: Interner> QuantifiedWhereClause<I> {
@@ -1189,11 +1409,53 @@ impl<T> Binders<T> {
         }
     }
 
+    /// Creates binders with the given `kinds`, computing the bound
+    /// value from `op`. `op` receives one parameter per entry in
+    /// `kinds`, each a reference to the corresponding bound variable
+    /// (at the innermost binder level).
+    pub fn from_fn<I, OP>(kinds: Vec<ParameterKind<()>>, op: OP, interner: &I) -> Binders<T>
+    where
+        I: Interner,
+        OP: FnOnce(&[Parameter<I>]) -> T,
+    {
+        let parameters: Vec<_> = bound_parameters(&kinds, interner).collect();
+        let value = op(&parameters);
+        Binders {
+            binders: kinds,
+            value,
+        }
+    }
+
+    /// Returns references to each of the variables bound by `self`,
+    /// in order, relative to the innermost binder. This is the
+    /// identity substitution for `self`.
+    pub fn enumerate_params<'a, I: Interner>(
+        &'a self,
+        interner: &'a I,
+    ) -> impl Iterator<Item = Parameter<I>> + 'a {
+        bound_parameters(&self.binders, interner)
+    }
+
     pub fn len(&self) -> usize {
         self.binders.len()
     }
 }
 
+/// References to the variables bound by `kinds`, relative to the
+/// innermost binder.
+fn bound_parameters<'a, I: Interner>(
+    kinds: &'a [ParameterKind<()>],
+    interner: &'a I,
+) -> impl Iterator<Item = Parameter<I>> + 'a {
+    kinds.iter().enumerate().map(move |(index, kind)| {
+        let bound_var = BoundVar::new(DebruijnIndex::INNERMOST, index);
+        match kind {
+            ParameterKind::Ty(()) => bound_var.to_ty(interner).cast(interner),
+            ParameterKind::Lifetime(()) => bound_var.to_lifetime(interner).cast(interner),
+        }
+    })
+}
+
 impl<T, I> Binders<T>
 where
     T: Fold<I, I> + HasInterner<Interner = I>,
@@ -1701,6 +1963,60 @@ impl<I: Interner> Substitution<I> {
         self.parameters(interner).len()
     }
 
+    /// Returns a new substitution with `extra` appended after the
+    /// existing parameters.
+    pub fn extend(
+        self,
+        extra: impl IntoIterator<Item = Parameter<I>>,
+        interner: &I,
+    ) -> Substitution<I> {
+        Self::from(interner, self.iter(interner).cloned().chain(extra))
+    }
+
+    /// Returns a new substitution containing only the first `len`
+    /// parameters. If `len` is at least `self.len()`, this is a no-op.
+    pub fn truncate(self, len: usize, interner: &I) -> Substitution<I> {
+        if len >= self.len(interner) {
+            return self;
+        }
+        Self::from(interner, self.iter(interner).take(len).cloned())
+    }
+
+    /// Returns a new substitution with the parameters of `other`
+    /// appended after those of `self`, e.g. to combine the
+    /// substitutions for an outer and an inner binder.
+    pub fn concat(self, other: Substitution<I>, interner: &I) -> Substitution<I> {
+        self.extend(other.iter(interner).cloned(), interner)
+    }
+
+    /// Returns a new substitution containing only the type parameters
+    /// of `self`, in order.
+    pub fn restrict_to_types(&self, interner: &I) -> Substitution<I> {
+        Self::from(
+            interner,
+            self.iter(interner).filter(|p| p.is_ty(interner)).cloned(),
+        )
+    }
+
+    /// Returns a new substitution containing only the lifetime
+    /// parameters of `self`, in order.
+    pub fn restrict_to_lifetimes(&self, interner: &I) -> Substitution<I> {
+        Self::from(
+            interner,
+            self.iter(interner).filter(|p| !p.is_ty(interner)).cloned(),
+        )
+    }
+
+    /// Splits the substitution into its first `n` parameters and the
+    /// rest; the inverse of `concat`. Panics if `n > self.len()`.
+    pub fn split_at(&self, n: usize, interner: &I) -> (Substitution<I>, Substitution<I>) {
+        let (prefix, suffix) = self.parameters(interner).split_at(n);
+        (
+            Self::from(interner, prefix.iter().cloned()),
+            Self::from(interner, suffix.iter().cloned()),
+        )
+    }
+
     /// A substitution is an **identity substitution** if it looks
     /// like this
     ///
@@ -24,10 +24,17 @@ macro_rules! ty {
     };
 
     (alias (item $n:tt) $($arg:tt)*) => {
-        $crate::TyData::Alias(AliasTy {
+        $crate::TyData::Alias(AliasTy::Projection(ProjectionTy {
             associated_ty_id: AssocTypeId(chalk_ir::interner::RawId { index: $n }),
             substitution: $crate::Substitution::from(&chalk_ir::interner::ChalkIr, vec![$(arg!($arg)),*] as Vec<$crate::Parameter<_>>),
-        }).intern(&chalk_ir::interner::ChalkIr)
+        })).intern(&chalk_ir::interner::ChalkIr)
+    };
+
+    (opaque (item $n:tt) $($arg:tt)*) => {
+        $crate::TyData::Alias(AliasTy::Opaque(OpaqueTy {
+            opaque_ty_id: OpaqueTyId(chalk_ir::interner::RawId { index: $n }),
+            substitution: $crate::Substitution::from(&chalk_ir::interner::ChalkIr, vec![$(arg!($arg)),*] as Vec<$crate::Parameter<_>>),
+        })).intern(&chalk_ir::interner::ChalkIr)
     };
 
     (infer $b:expr) => {
@@ -1,7 +1,7 @@
 use crate::interner::ChalkIr;
 use crate::{
-    debug::SeparatorTraitRef, AliasTy, ApplicationTy, AssocTypeId, Goal, Goals, Lifetime,
-    Parameter, ProgramClause, ProgramClauseImplication, ProgramClauses, QuantifiedWhereClauses,
+    debug::SeparatorTraitRef, ApplicationTy, AssocTypeId, Goal, Goals, Lifetime, Parameter,
+    ProgramClause, ProgramClauseImplication, ProgramClauses, ProjectionTy, QuantifiedWhereClauses,
     StructId, Substitution, TraitId, Ty,
 };
 use std::cell::RefCell;
@@ -31,9 +31,19 @@ pub trait DebugContext {
         fmt: &mut fmt::Formatter<'_>,
     ) -> Result<(), fmt::Error>;
 
-    fn debug_alias(
+    /// Prints just the name of an associated type, without its
+    /// trait. Defaults to the output of `debug_assoc_type_id`.
+    fn debug_assoc_type_name(
         &self,
-        alias: &AliasTy<ChalkIr>,
+        id: AssocTypeId<ChalkIr>,
+        fmt: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        self.debug_assoc_type_id(id, fmt)
+    }
+
+    fn debug_projection_ty(
+        &self,
+        projection: &ProjectionTy<ChalkIr>,
         fmt: &mut fmt::Formatter<'_>,
     ) -> Result<(), fmt::Error>;
 
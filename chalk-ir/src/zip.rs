@@ -195,7 +195,9 @@ macro_rules! eq_zip {
 
 eq_zip!(I => StructId<I>);
 eq_zip!(I => TraitId<I>);
+eq_zip!(I => ImplId<I>);
 eq_zip!(I => AssocTypeId<I>);
+eq_zip!(I => OpaqueTyId<I>);
 eq_zip!(I => TypeName<I>);
 eq_zip!(I => QuantifierKind);
 eq_zip!(I => PhantomData<I>);
@@ -233,10 +235,14 @@ struct_zip!(impl[
 });
 struct_zip!(impl[I: Interner] Zip<I> for ApplicationTy<I> { name, substitution });
 struct_zip!(impl[I: Interner] Zip<I> for DynTy<I> { bounds });
-struct_zip!(impl[I: Interner] Zip<I> for AliasTy<I> {
+struct_zip!(impl[I: Interner] Zip<I> for ProjectionTy<I> {
     associated_ty_id,
     substitution,
 });
+struct_zip!(impl[I: Interner] Zip<I> for OpaqueTy<I> {
+    opaque_ty_id,
+    substitution,
+});
 struct_zip!(impl[I: Interner] Zip<I> for Normalize<I> { alias, ty });
 struct_zip!(impl[I: Interner] Zip<I> for AliasEq<I> { alias, ty });
 struct_zip!(impl[I: Interner] Zip<I> for EqGoal<I> { a, b });
@@ -321,9 +327,10 @@ macro_rules! enum_zip {
     }
 }
 
-enum_zip!(impl<I> for WellFormed<I> { Trait, Ty });
+enum_zip!(impl<I> for WellFormed<I> { Trait, Ty, Impl, Struct });
 enum_zip!(impl<I> for FromEnv<I> { Trait, Ty });
 enum_zip!(impl<I> for WhereClause<I> { Implemented, AliasEq });
+enum_zip!(impl<I> for AliasTy<I> { Projection, Opaque });
 enum_zip!(impl<I> for DomainGoal<I> {
     Holds,
     WellFormed,
@@ -433,3 +440,63 @@ impl<I: Interner> Zip<I> for ProgramClause<I> {
         Zip::zip_with(zipper, a.data(interner), b.data(interner))
     }
 }
+
+/// A zipper that requires the two values to be structurally equal,
+/// comparing every nested type by its `TyData` rather than via
+/// `Interner::interned_ty_eq`. Used by `Interner::term_eq`.
+pub(crate) struct TermEqZipper<'i, I: Interner> {
+    interner: &'i I,
+}
+
+impl<'i, I: Interner> TermEqZipper<'i, I> {
+    pub(crate) fn new(interner: &'i I) -> Self {
+        TermEqZipper { interner }
+    }
+
+    pub(crate) fn zip_ty_data(&mut self, a: &TyData<I>, b: &TyData<I>) -> Fallible<()> {
+        match (a, b) {
+            (TyData::Apply(a), TyData::Apply(b)) => Zip::zip_with(self, a, b),
+            (TyData::Dyn(a), TyData::Dyn(b)) => Zip::zip_with(self, a, b),
+            (TyData::Alias(a), TyData::Alias(b)) => Zip::zip_with(self, a, b),
+            (TyData::Function(a), TyData::Function(b)) => {
+                if a.num_binders != b.num_binders {
+                    return Err(NoSolution);
+                }
+                Zip::zip_with(self, &a.substitution, &b.substitution)
+            }
+            (TyData::Placeholder(a), TyData::Placeholder(b)) if a == b => Ok(()),
+            (TyData::BoundVar(a), TyData::BoundVar(b)) if a == b => Ok(()),
+            (TyData::InferenceVar(a), TyData::InferenceVar(b)) if a == b => Ok(()),
+            _ => Err(NoSolution),
+        }
+    }
+}
+
+impl<'i, I: Interner> Zipper<'i, I> for TermEqZipper<'i, I> {
+    fn zip_tys(&mut self, a: &Ty<I>, b: &Ty<I>) -> Fallible<()> {
+        let interner = self.interner;
+        self.zip_ty_data(a.data(interner), b.data(interner))
+    }
+
+    fn zip_lifetimes(&mut self, a: &Lifetime<I>, b: &Lifetime<I>) -> Fallible<()> {
+        let interner = self.interner;
+        if a.data(interner) != b.data(interner) {
+            return Err(NoSolution);
+        }
+        Ok(())
+    }
+
+    fn zip_binders<T>(&mut self, a: &Binders<T>, b: &Binders<T>) -> Fallible<()>
+    where
+        T: Zip<I> + Fold<I, I, Result = T>,
+    {
+        if a.binders != b.binders {
+            return Err(NoSolution);
+        }
+        Zip::zip_with(self, &a.value, &b.value)
+    }
+
+    fn interner(&self) -> &'i I {
+        self.interner
+    }
+}
@@ -33,6 +33,7 @@ pub struct StructDefn {
     pub where_clauses: Vec<QuantifiedWhereClause>,
     pub fields: Vec<Field>,
     pub flags: StructFlags,
+    pub lang_item: Option<LangItem>,
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -46,14 +47,22 @@ pub struct TraitDefn {
     pub name: Identifier,
     pub parameter_kinds: Vec<ParameterKind>,
     pub where_clauses: Vec<QuantifiedWhereClause>,
+    pub assoc_const_defns: Vec<AssocConstDefn>,
     pub assoc_ty_defns: Vec<AssocTyDefn>,
     pub flags: TraitFlags,
     pub well_known: Option<WellKnownTrait>,
+    pub maybe_bound: Option<Identifier>,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum WellKnownTrait {
     SizedTrait,
+    DropTrait,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum LangItem {
+    PhantomData,
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -74,6 +83,13 @@ pub struct AssocTyDefn {
     pub where_clauses: Vec<QuantifiedWhereClause>,
 }
 
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AssocConstDefn {
+    pub name: Identifier,
+    pub ty: Ty,
+    pub has_default: bool,
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum ParameterKind {
     Ty(Identifier),
@@ -138,6 +154,7 @@ pub struct Impl {
     pub trait_ref: TraitRef,
     pub polarity: Polarity,
     pub where_clauses: Vec<QuantifiedWhereClause>,
+    pub assoc_const_values: Vec<AssocConstValue>,
     pub assoc_ty_values: Vec<AssocTyValue>,
     pub impl_type: ImplType,
 }
@@ -156,6 +173,12 @@ pub struct AssocTyValue {
     pub default: bool,
 }
 
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AssocConstValue {
+    pub name: Identifier,
+    pub ty: Ty,
+}
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum Ty {
     Id {
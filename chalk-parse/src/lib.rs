@@ -9,62 +9,90 @@ pub mod ast;
 #[rustfmt::skip]
 lalrpop_mod!(pub parser);
 
-use lalrpop_util::ParseError;
-use std::fmt::Write;
+use std::fmt;
 
-type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+type Result<T> = std::result::Result<T, ParseError>;
 
-pub fn parse_program(text: &str) -> Result<ast::Program> {
-    match parser::ProgramParser::new().parse(text) {
-        Ok(v) => Ok(v),
-        Err(e) => Err(format!("parse error: {:?}", e))?,
-    }
+/// An error encountered while parsing chalk source text, pointing at
+/// the token where parsing failed.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ParseError {
+    /// The line (starting from 1) of the offending token.
+    pub line: usize,
+
+    /// The column (starting from 1, counted in characters) of the
+    /// offending token.
+    pub column: usize,
+
+    /// The tokens that the parser would have accepted instead; empty
+    /// if the parser does not know.
+    pub expected: Vec<String>,
+
+    /// The offending token, or `EOF` if the input ended too early.
+    pub got: String,
 }
 
-pub fn parse_ty(text: &str) -> Result<ast::Ty> {
-    match parser::TyParser::new().parse(text) {
-        Ok(v) => Ok(v),
-        Err(e) => Err(format!("error parsing `{}`: {:?}", text, e))?,
+impl ParseError {
+    fn new<T, E: fmt::Display>(text: &str, error: lalrpop_util::ParseError<usize, T, E>) -> Self {
+        use lalrpop_util::ParseError::*;
+
+        let (offset, expected, got) = match error {
+            InvalidToken { location } => {
+                let got = text[location..].chars().next().map(String::from);
+                (location, vec![], got.unwrap_or_default())
+            }
+            UnrecognizedEOF { location, expected } => (location, expected, "EOF".to_string()),
+            UnrecognizedToken {
+                token: (start, _, end),
+                expected,
+            } => (start, expected, text[start..end].to_string()),
+            ExtraToken {
+                token: (start, _, end),
+            } => (start, vec![], text[start..end].to_string()),
+            User { error } => (text.len(), vec![], error.to_string()),
+        };
+
+        let before = &text[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        ParseError {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            expected,
+            got,
+        }
     }
 }
 
-pub fn parse_goal(text: &str) -> Result<Box<ast::Goal>> {
-    match parser::GoalParser::new().parse(text) {
-        Ok(v) => Ok(v),
-        Err(e) => {
-            let position_string = |start: usize, end: usize| {
-                let mut output = String::new();
-                let text = text.replace("\n", " ").replace("\r", " ");
-                writeln!(output, "position: `{}`", text).expect("str-write cannot fail");
-                output.push_str(&" ".repeat(11 + start));
-                output.push_str(&"^".repeat(end - start));
-                output.push_str("\n");
-                output
-            };
-            match e {
-                ParseError::InvalidToken { location } => Err(format!(
-                    "parse error: {:?}\n{}",
-                    e,
-                    position_string(location, location + 1)
-                ))?,
-                ParseError::UnrecognizedToken {
-                    token: (start, _, end),
-                    ..
-                } => Err(format!(
-                    "parse error: {:?}\n{}",
-                    e,
-                    position_string(start, end)
-                ))?,
-                ParseError::ExtraToken {
-                    token: (start, _, end),
-                    ..
-                } => Err(format!(
-                    "parse error: {:?}\n{}",
-                    e,
-                    position_string(start, end)
-                ))?,
-                _ => Err(format!("parse error: {:?}", e))?,
-            }
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "parse error at line {}, column {}: unexpected `{}`",
+            self.line, self.column, self.got
+        )?;
+        if !self.expected.is_empty() {
+            write!(f, ", expected one of {}", self.expected.join(", "))?;
         }
+        Ok(())
     }
 }
+
+impl std::error::Error for ParseError {}
+
+pub fn parse_program(text: &str) -> Result<ast::Program> {
+    parser::ProgramParser::new()
+        .parse(text)
+        .map_err(|e| ParseError::new(text, e))
+}
+
+pub fn parse_ty(text: &str) -> Result<ast::Ty> {
+    parser::TyParser::new()
+        .parse(text)
+        .map_err(|e| ParseError::new(text, e))
+}
+
+pub fn parse_goal(text: &str) -> Result<Box<ast::Goal>> {
+    parser::GoalParser::new()
+        .parse(text)
+        .map_err(|e| ParseError::new(text, e))
+}
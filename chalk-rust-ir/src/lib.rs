@@ -5,14 +5,18 @@
 //! compiler.
 
 use chalk_derive::{Fold, HasInterner};
+use chalk_engine::fallible::Fallible;
 use chalk_ir::cast::Cast;
-use chalk_ir::fold::{shift::Shift, Fold, Folder};
+use chalk_ir::debug::Angle;
+use chalk_ir::fold::{shift::Shift, Fold, Folder, SuperFold};
 use chalk_ir::interner::{HasInterner, Interner, TargetInterner};
 use chalk_ir::{
-    AliasEq, AliasTy, AssocTypeId, Binders, BoundVar, DebruijnIndex, ImplId, LifetimeData,
-    Parameter, ParameterKind, QuantifiedWhereClause, StructId, Substitution, TraitId, TraitRef, Ty,
-    TyData, TypeName, WhereClause,
+    AliasEq, AliasTy, AssocTypeId, Binders, BoundVar, DebruijnIndex, ImplId, Lifetime,
+    LifetimeData, OpaqueTyId, Parameter, ParameterKind, ProjectionTy, QuantifiedWhereClause,
+    StructId, Substitution, TraitId, TraitRef, Ty, TyData, TypeName, WhereClause,
 };
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
 use std::iter;
 
 /// Identifier for an "associated type value" found in some impl.
@@ -21,12 +25,37 @@ pub struct AssociatedTyValueId<I: Interner>(pub I::DefId);
 
 chalk_ir::id_fold!(AssociatedTyValueId);
 
+/// Identifier for an "associated const" declared in a trait.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AssocConstId<I: Interner>(pub I::DefId);
+
+chalk_ir::id_fold!(AssocConstId);
+
+/// Identifier for an "associated const value" found in some impl.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AssociatedConstValueId<I: Interner>(pub I::DefId);
+
+chalk_ir::id_fold!(AssociatedConstValueId);
+
+/// Identifier for a function item, e.g. `foo` in `fn foo<T>(x: T) -> T`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FnDefId<I: Interner>(pub I::DefId);
+
+chalk_ir::id_fold!(FnDefId);
+
+/// Identifier for a closure expression.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ClosureId<I: Interner>(pub I::DefId);
+
+chalk_ir::id_fold!(ClosureId);
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct ImplDatum<I: Interner> {
     pub polarity: Polarity,
     pub binders: Binders<ImplDatumBound<I>>,
     pub impl_type: ImplType,
     pub associated_ty_value_ids: Vec<AssociatedTyValueId<I>>,
+    pub associated_const_value_ids: Vec<AssociatedConstValueId<I>>,
 }
 
 impl<I: Interner> ImplDatum<I> {
@@ -37,6 +66,155 @@ impl<I: Interner> ImplDatum<I> {
     pub fn trait_id(&self) -> TraitId<I> {
         self.binders.value.trait_ref.trait_id
     }
+
+    /// Returns the indices of the impl's type parameters that are not
+    /// constrained, e.g. `T` in `impl<T> Foo for u32`. A parameter is
+    /// constrained if it appears in the trait reference, or in the
+    /// value of an `AliasEq` where clause like `I: Iterator<Item = T>`
+    /// whose alias only mentions constrained parameters. Appearing
+    /// anywhere else in the where clauses does not constrain a
+    /// parameter. Lifetime parameters are never reported.
+    pub fn unconstrained_params(&self, interner: &I) -> Vec<usize> {
+        let bound = &self.binders.value;
+        let mut constrained =
+            collect_bound_vars(&bound.trait_ref, DebruijnIndex::INNERMOST, interner);
+
+        // The where clauses sit under their own binder, so the impl's
+        // parameters are one level out from them.
+        let alias_eqs: Vec<_> = bound
+            .where_clauses
+            .iter()
+            .filter_map(|where_clause| match &where_clause.value {
+                WhereClause::AliasEq(alias_eq) => Some((
+                    collect_bound_vars(&alias_eq.alias, DebruijnIndex::ONE, interner),
+                    collect_bound_vars(&alias_eq.ty, DebruijnIndex::ONE, interner),
+                )),
+                WhereClause::Implemented(_) => None,
+            })
+            .collect();
+
+        // Constraining one parameter may constrain the alias of
+        // another clause, so iterate until nothing changes.
+        loop {
+            let len = constrained.len();
+            for (alias_vars, ty_vars) in &alias_eqs {
+                if alias_vars.is_subset(&constrained) {
+                    constrained.extend(ty_vars.iter().cloned());
+                }
+            }
+            if constrained.len() == len {
+                break;
+            }
+        }
+
+        self.binders
+            .binders
+            .iter()
+            .enumerate()
+            .filter(|(index, kind)| kind.is_ty() && !constrained.contains(index))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// True if `ty_name` appears anywhere in the impl's trait reference
+    /// or where clauses, so the impl has to be rechecked when the
+    /// definition of `ty_name` changes.
+    pub fn contains_type(&self, ty_name: TypeName<I>, interner: &I) -> bool {
+        let bound = &self.binders.value;
+        collect_type_names(&bound.trait_ref, interner).contains(&ty_name)
+            || collect_type_names(&bound.where_clauses, interner).contains(&ty_name)
+    }
+}
+
+/// Returns the names of all application types that appear in `value`.
+fn collect_type_names<I: Interner, T: Fold<I>>(value: &T, interner: &I) -> BTreeSet<TypeName<I>> {
+    let mut collector = TypeNameCollector {
+        interner,
+        names: BTreeSet::new(),
+    };
+    value
+        .fold_with(&mut collector, DebruijnIndex::INNERMOST)
+        .unwrap();
+    collector.names
+}
+
+/// Collects the names of the application types in the folded value,
+/// leaving the value itself unchanged.
+struct TypeNameCollector<'i, I: Interner> {
+    interner: &'i I,
+    names: BTreeSet<TypeName<I>>,
+}
+
+impl<'i, I: Interner> Folder<'i, I> for TypeNameCollector<'i, I> {
+    fn as_dyn(&mut self) -> &mut dyn Folder<'i, I> {
+        self
+    }
+
+    fn fold_ty(&mut self, ty: &Ty<I>, outer_binder: DebruijnIndex) -> Fallible<Ty<I>> {
+        if let TyData::Apply(apply) = ty.data(self.interner) {
+            self.names.insert(apply.name);
+        }
+        ty.super_fold_with(self.as_dyn(), outer_binder)
+    }
+
+    fn interner(&self) -> &'i I {
+        self.interner
+    }
+
+    fn target_interner(&self) -> &'i I {
+        self.interner()
+    }
+}
+
+/// Returns the indices of the type variables in `value` that are bound
+/// by the binder `outer_binder` levels out from it.
+fn collect_bound_vars<I: Interner, T: Fold<I>>(
+    value: &T,
+    outer_binder: DebruijnIndex,
+    interner: &I,
+) -> BTreeSet<usize> {
+    let mut collector = BoundVarCollector {
+        interner,
+        indices: BTreeSet::new(),
+    };
+    value
+        .fold_with(&mut collector, outer_binder)
+        .expect("collecting bound variables cannot fail");
+    collector.indices
+}
+
+/// Collects the indices of all type variables bound by the innermost
+/// binder of the folded value, leaving the value itself unchanged.
+struct BoundVarCollector<'i, I: Interner> {
+    interner: &'i I,
+    indices: BTreeSet<usize>,
+}
+
+impl<'i, I: Interner> Folder<'i, I> for BoundVarCollector<'i, I> {
+    fn as_dyn(&mut self) -> &mut dyn Folder<'i, I> {
+        self
+    }
+
+    fn fold_free_var_ty(
+        &mut self,
+        bound_var: BoundVar,
+        outer_binder: DebruijnIndex,
+    ) -> Fallible<Ty<I>> {
+        if let Some(index) = bound_var.index_if_innermost() {
+            self.indices.insert(index);
+        }
+        Ok(bound_var
+            .shifted_in_from(outer_binder)
+            .to_ty(self.interner()))
+    }
+
+    fn interner(&self) -> &'i I {
+        self.interner
+    }
+
+    fn target_interner(&self) -> &'i I {
+        self.interner()
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, HasInterner, Fold)]
@@ -45,12 +223,137 @@ pub struct ImplDatumBound<I: Interner> {
     pub where_clauses: Vec<QuantifiedWhereClause<I>>,
 }
 
+/// Builds an `ImplDatum` one piece at a time, taking care of the
+/// binders. Each `with_*_param` call adds a generic parameter to the
+/// impl and returns a reference to it that can be used directly in
+/// the trait reference and the where clauses, which are shifted into
+/// their own binders as they are added. E.g., for
+/// `impl<T> Foo for Vec<T> where T: Clone`:
+///
+/// ```ignore
+/// let mut builder = ImplDatumBuilder::new(interner);
+/// let t = builder.with_ty_param();
+/// builder.set_trait_ref(/* Vec<t>: Foo */);
+/// builder.add_where_clause(/* t: Clone */);
+/// let impl_datum = builder.build();
+/// ```
+pub struct ImplDatumBuilder<'i, I: Interner> {
+    interner: &'i I,
+    binders: Vec<ParameterKind<()>>,
+    trait_ref: Option<TraitRef<I>>,
+    where_clauses: Vec<QuantifiedWhereClause<I>>,
+    polarity: Polarity,
+    impl_type: ImplType,
+}
+
+impl<'i, I: Interner> ImplDatumBuilder<'i, I> {
+    /// Starts building a positive, local impl with no generic
+    /// parameters.
+    pub fn new(interner: &'i I) -> Self {
+        ImplDatumBuilder {
+            interner,
+            binders: vec![],
+            trait_ref: None,
+            where_clauses: vec![],
+            polarity: Polarity::Positive,
+            impl_type: ImplType::Local,
+        }
+    }
+
+    fn next_bound_var(&mut self, kind: ParameterKind<()>) -> BoundVar {
+        let bound_var = BoundVar::new(DebruijnIndex::INNERMOST, self.binders.len());
+        self.binders.push(kind);
+        bound_var
+    }
+
+    /// Adds a type parameter to the impl, returning the type that
+    /// refers to it.
+    pub fn with_ty_param(&mut self) -> Ty<I> {
+        self.next_bound_var(ParameterKind::Ty(()))
+            .to_ty(self.interner)
+    }
+
+    /// Adds a lifetime parameter to the impl, returning the lifetime
+    /// that refers to it.
+    pub fn with_lifetime_param(&mut self) -> Lifetime<I> {
+        self.next_bound_var(ParameterKind::Lifetime(()))
+            .to_lifetime(self.interner)
+    }
+
+    /// Sets the trait reference that the impl implements.
+    pub fn set_trait_ref(&mut self, trait_ref: TraitRef<I>) -> &mut Self {
+        self.trait_ref = Some(trait_ref);
+        self
+    }
+
+    /// Adds a where clause to the impl. The where clause refers to the
+    /// impl's parameters as returned by `with_ty_param` and
+    /// `with_lifetime_param`.
+    pub fn add_where_clause(&mut self, where_clause: WhereClause<I>) -> &mut Self {
+        self.where_clauses.push(Binders {
+            binders: vec![],
+            value: where_clause.shifted_in(self.interner),
+        });
+        self
+    }
+
+    pub fn set_polarity(&mut self, polarity: Polarity) -> &mut Self {
+        self.polarity = polarity;
+        self
+    }
+
+    pub fn set_impl_type(&mut self, impl_type: ImplType) -> &mut Self {
+        self.impl_type = impl_type;
+        self
+    }
+
+    /// Creates the `ImplDatum`, which has no associated values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no trait reference was set.
+    pub fn build(self) -> ImplDatum<I> {
+        let trait_ref = self
+            .trait_ref
+            .expect("ImplDatumBuilder::build called without a trait reference");
+        ImplDatum {
+            polarity: self.polarity,
+            binders: Binders {
+                binders: self.binders,
+                value: ImplDatumBound {
+                    trait_ref,
+                    where_clauses: self.where_clauses,
+                },
+            },
+            impl_type: self.impl_type,
+            associated_ty_value_ids: vec![],
+            associated_const_value_ids: vec![],
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum ImplType {
     Local,
     External,
 }
 
+impl ImplType {
+    pub fn is_local(&self) -> bool {
+        match *self {
+            ImplType::Local => true,
+            ImplType::External => false,
+        }
+    }
+
+    pub fn is_external(&self) -> bool {
+        match *self {
+            ImplType::Local => false,
+            ImplType::External => true,
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct DefaultImplDatum<I: Interner> {
     pub binders: Binders<DefaultImplDatumBound<I>>,
@@ -67,12 +370,38 @@ pub struct StructDatum<I: Interner> {
     pub binders: Binders<StructDatumBound<I>>,
     pub id: StructId<I>,
     pub flags: StructFlags,
+
+    /// Unstable language features that this struct's declaration relies on.
+    pub unstable_features: Vec<UnstableFeature>,
 }
 
 impl<I: Interner> StructDatum<I> {
     pub fn name(&self, interner: &I) -> TypeName<I> {
         self.id.cast(interner)
     }
+
+    pub fn uses_unstable(&self) -> bool {
+        !self.unstable_features.is_empty()
+    }
+
+    /// The number of fields declared on this struct.
+    pub fn field_count(&self) -> usize {
+        self.binders.value.fields.len()
+    }
+
+    /// Iterates over the types of the struct's fields, in declaration
+    /// order. The types still reference the struct's generic
+    /// parameters as bound variables, so they must be substituted
+    /// before being used outside of the struct's binders.
+    pub fn field_types<'a>(&'a self) -> impl Iterator<Item = &'a Ty<I>> + 'a {
+        self.binders.value.fields.iter()
+    }
+
+    /// True if any of the struct's field types satisfies `f`. As with
+    /// `field_types`, the types are not substituted.
+    pub fn any_field_satisfies<F: Fn(&Ty<I>) -> bool>(&self, f: F) -> bool {
+        self.field_types().any(f)
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Fold, HasInterner)]
@@ -81,12 +410,172 @@ pub struct StructDatumBound<I: Interner> {
     pub where_clauses: Vec<QuantifiedWhereClause<I>>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct StructFlags {
     pub upstream: bool,
     pub fundamental: bool,
 }
 
+/// ADTs share the id space of structs, which are the only kind of ADT
+/// that is lowered so far.
+pub use chalk_ir::StructId as AdtId;
+
+/// A struct, enum or union. Generalizes `StructDatum`, which is the
+/// special case of a struct with a single variant.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AdtDatum<I: Interner> {
+    pub binders: Binders<AdtDatumBound<I>>,
+    pub id: AdtId<I>,
+    pub kind: AdtKind,
+    pub flags: StructFlags,
+    pub unstable_features: Vec<UnstableFeature>,
+}
+
+impl<I: Interner> AdtDatum<I> {
+    pub fn name(&self, interner: &I) -> TypeName<I> {
+        self.id.cast(interner)
+    }
+
+    pub fn variants(&self) -> &[AdtVariantDatum<I>] {
+        &self.binders.value.variants
+    }
+
+    /// The fields of a struct or union, which should have exactly one
+    /// variant; `None` for enums, whose fields depend on the variant,
+    /// and for a struct or union that was built without a variant.
+    pub fn fields(&self) -> Option<&[Ty<I>]> {
+        match self.kind {
+            AdtKind::Struct | AdtKind::Union => {
+                self.variants().first().map(|variant| &variant.fields[..])
+            }
+            AdtKind::Enum => None,
+        }
+    }
+}
+
+impl<I: Interner> From<StructDatum<I>> for AdtDatum<I> {
+    fn from(struct_datum: StructDatum<I>) -> Self {
+        let StructDatum {
+            binders,
+            id,
+            flags,
+            unstable_features,
+        } = struct_datum;
+        AdtDatum {
+            binders: binders.map(|bound| AdtDatumBound {
+                variants: vec![AdtVariantDatum {
+                    fields: bound.fields,
+                }],
+                where_clauses: bound.where_clauses,
+            }),
+            id,
+            kind: AdtKind::Struct,
+            flags,
+            unstable_features,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Fold, HasInterner)]
+pub struct AdtDatumBound<I: Interner> {
+    pub variants: Vec<AdtVariantDatum<I>>,
+    pub where_clauses: Vec<QuantifiedWhereClause<I>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Fold, HasInterner)]
+pub struct AdtVariantDatum<I: Interner> {
+    pub fields: Vec<Ty<I>>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AdtKind {
+    Struct,
+    Enum,
+    Union,
+}
+
+/// A function item like `fn foo<T>(x: T) -> T where T: Clone`. Each
+/// function item has its own zero-sized type; this describes its
+/// signature so that e.g. the `Fn` traits can be proven for it.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FnDefDatum<I: Interner> {
+    pub id: FnDefId<I>,
+    pub binders: Binders<FnDefDatumBound<I>>,
+}
+
+impl<I: Interner> FnDefDatum<I> {
+    /// The argument types and return type of the function, under the
+    /// function's binders.
+    pub fn sig(&self) -> Binders<(&[Ty<I>], &Ty<I>)> {
+        self.binders
+            .map_ref(|bound| (&bound.argument_types[..], &bound.return_type))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Fold, HasInterner)]
+pub struct FnDefDatumBound<I: Interner> {
+    pub argument_types: Vec<Ty<I>>,
+    pub return_type: Ty<I>,
+    pub where_clauses: Vec<QuantifiedWhereClause<I>>,
+}
+
+/// A closure, e.g. `|x| x + y`. The binders are those of the item
+/// that contains the closure; the upvars are the types of the
+/// variables it captures, such as `y` above.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ClosureDatum<I: Interner> {
+    pub id: ClosureId<I>,
+    pub kind: ClosureKind,
+    pub binders: Binders<ClosureDatumBound<I>>,
+}
+
+impl<I: Interner> ClosureDatum<I> {
+    pub fn kind(&self) -> ClosureKind {
+        self.kind
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Fold, HasInterner)]
+pub struct ClosureDatumBound<I: Interner> {
+    pub upvars: Vec<Ty<I>>,
+    pub argument_types: Vec<Ty<I>>,
+    pub return_type: Ty<I>,
+}
+
+/// Which of the closure traits a closure implements, as determined by
+/// how it uses its upvars: `Fn` if it only reads them (e.g. captures by
+/// shared reference), `FnMut` if it mutates them (e.g. captures by
+/// mutable reference), and `FnOnce` if it moves out of them.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ClosureKind {
+    Fn,
+    FnMut,
+    FnOnce,
+}
+
+impl ClosureKind {
+    /// True if a closure of kind `self` implements the closure trait
+    /// corresponding to `trait_kind`. Every `Fn` closure is also
+    /// `FnMut`, and every `FnMut` closure is also `FnOnce`; the
+    /// variants are declared in that order.
+    pub fn implements(self, trait_kind: ClosureKind) -> bool {
+        self <= trait_kind
+    }
+}
+
+/// An unstable language feature that a datum can rely on, e.g. because
+/// it was declared with a feature-gated attribute. This is only metadata
+/// for front-ends to report; chalk itself does not act on it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum UnstableFeature {
+    /// The `#[fundamental]` attribute.
+    Fundamental,
+    /// The `#[marker]` attribute on traits.
+    MarkerTraitAttr,
+    /// `auto trait` declarations.
+    AutoTraits,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 /// A rust intermediate representation (rust_ir) of a Trait Definition. For
 /// example, given the following rust code:
@@ -124,9 +613,65 @@ pub struct TraitDatum<I: Interner> {
 
     pub associated_ty_ids: Vec<AssocTypeId<I>>,
 
+    pub associated_const_ids: Vec<AssocConstId<I>>,
+
     /// If this is a well-known trait, which one? If `None`, this is a regular,
     /// user-defined trait.
     pub well_known: Option<WellKnownTrait>,
+
+    /// Whether `Self: Sized` is implied by the trait. This is the
+    /// default; it is false for traits declared `trait Foo: ?Sized`.
+    pub self_sized: bool,
+
+    /// Unstable language features that this trait's declaration relies on.
+    pub unstable_features: Vec<UnstableFeature>,
+}
+
+/// A list of the types that are "well known" to chalk. This is the
+/// counterpart of `WellKnownTrait` for structs: it lets chalk-solve
+/// recognize specific types, such as `PhantomData`, when reasoning
+/// about them.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub enum LangItem {
+    PhantomData,
+}
+
+/// Maps each `LangItem` to the struct that implements it, if any.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LangItems<I: Interner> {
+    structs: BTreeMap<LangItem, StructId<I>>,
+}
+
+impl<I: Interner> LangItems<I> {
+    pub fn new() -> Self {
+        LangItems {
+            structs: BTreeMap::new(),
+        }
+    }
+
+    pub fn insert_struct(&mut self, lang_item: LangItem, struct_id: StructId<I>) {
+        self.structs.insert(lang_item, struct_id);
+    }
+
+    pub fn struct_id(&self, lang_item: LangItem) -> Option<StructId<I>> {
+        self.structs.get(&lang_item).copied()
+    }
+
+    pub fn phantom_data_struct(&self) -> Option<StructId<I>> {
+        self.struct_id(LangItem::PhantomData)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (LangItem, StructId<I>)> + '_ {
+        self.structs
+            .iter()
+            .map(|(&lang_item, &struct_id)| (lang_item, struct_id))
+    }
+}
+
+impl<I: Interner> Default for LangItems<I> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// A list of the traits that are "well known" to chalk, which means that
@@ -136,23 +681,81 @@ pub enum WellKnownTrait {
     SizedTrait,
     CopyTrait,
     CloneTrait,
+    DropTrait,
 }
 
 impl<I: Interner> TraitDatum<I> {
     pub fn is_auto_trait(&self) -> bool {
-        self.flags.auto
+        self.flags.auto()
     }
 
     pub fn is_non_enumerable_trait(&self) -> bool {
-        self.flags.non_enumerable
+        self.flags.non_enumerable()
     }
 
     pub fn is_coinductive_trait(&self) -> bool {
-        self.flags.coinductive
+        self.flags.coinductive()
+    }
+
+    /// A marker trait is a trait with no items (methods, associated
+    /// types, ...), declared with `#[marker]`. Since there is nothing
+    /// an impl could disagree on, impls of a marker trait are allowed
+    /// to overlap, and coherence skips the overlap check for them.
+    /// Unlike Rust's informal notion of a "marker trait", a trait
+    /// without items that is not declared `#[marker]` is checked as
+    /// usual.
+    pub fn is_marker_trait(&self) -> bool {
+        self.flags.marker()
+    }
+
+    pub fn uses_unstable(&self) -> bool {
+        !self.unstable_features.is_empty()
+    }
+
+    /// Returns the bounds on `Self` that the trait implies without
+    /// them being written, given the id of the `Sized` trait. Like the
+    /// trait's own where clauses, these are expressed in terms of the
+    /// trait's binders.
+    pub fn implicit_self_bounds(
+        &self,
+        sized_trait: TraitId<I>,
+        interner: &I,
+    ) -> Vec<QuantifiedWhereClause<I>> {
+        if !self.self_sized {
+            return vec![];
+        }
+
+        // `Self` is the first of the trait's parameters; it is shifted in
+        // by one to account for the (empty) binder of the where clause.
+        let self_ty = BoundVar::new(DebruijnIndex::ONE, 0).to_ty(interner);
+        vec![Binders {
+            binders: vec![],
+            value: WhereClause::Implemented(TraitRef {
+                trait_id: sized_trait,
+                substitution: Substitution::from1(interner, self_ty),
+            }),
+        }]
+    }
+
+    /// Builds the projection `<Self as Trait<..>>::Assoc` for one of
+    /// this trait's associated types, as it would appear in the
+    /// trait's own where clauses (i.e., within the trait's binders,
+    /// with `Self` as `^0.0`). Only valid for associated types that
+    /// have no parameters of their own.
+    pub fn self_assoc_projection(
+        &self,
+        assoc_ty_id: AssocTypeId<I>,
+        interner: &I,
+    ) -> ProjectionTy<I> {
+        debug_assert!(self.associated_ty_ids.contains(&assoc_ty_id));
+        ProjectionTy {
+            associated_ty_id: assoc_ty_id,
+            substitution: Substitution::from(interner, self.binders.enumerate_params(interner)),
+        }
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, HasInterner, Fold)]
 pub struct TraitDatumBound<I: Interner> {
     /// Where clauses defined on the trait:
     ///
@@ -163,19 +766,65 @@ pub struct TraitDatumBound<I: Interner> {
     pub where_clauses: Vec<QuantifiedWhereClause<I>>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+/// The boolean flags of a trait, packed into a single byte. Use the
+/// accessor methods (`auto`, `marker`, ...) to read them and the
+/// corresponding `set_*` methods to change them.
+#[derive(Copy, Clone, Default, PartialEq, Eq, Hash)]
 pub struct TraitFlags {
+    bits: u8,
+}
+
+impl TraitFlags {
+    const AUTO: u8 = 1 << 0;
+    const MARKER: u8 = 1 << 1;
+    const UPSTREAM: u8 = 1 << 2;
+    const FUNDAMENTAL: u8 = 1 << 3;
+    const NON_ENUMERABLE: u8 = 1 << 4;
+    const COINDUCTIVE: u8 = 1 << 5;
+
+    fn get(&self, bit: u8) -> bool {
+        self.bits & bit != 0
+    }
+
+    fn set(&mut self, bit: u8, value: bool) {
+        if value {
+            self.bits |= bit;
+        } else {
+            self.bits &= !bit;
+        }
+    }
+
     /// An "auto trait" is one that is "automatically implemented" for every
     /// struct, so long as no explicit impl is given.
     ///
     /// Examples are `Send` and `Sync`.
-    pub auto: bool,
+    pub fn auto(&self) -> bool {
+        self.get(Self::AUTO)
+    }
+
+    pub fn set_auto(&mut self, value: bool) {
+        self.set(Self::AUTO, value)
+    }
+
+    /// Whether the trait was declared `#[marker]`; see
+    /// `TraitDatum::is_marker_trait`.
+    pub fn marker(&self) -> bool {
+        self.get(Self::MARKER)
+    }
 
-    pub marker: bool,
+    pub fn set_marker(&mut self, value: bool) {
+        self.set(Self::MARKER, value)
+    }
 
     /// Indicate that a trait is defined upstream (in a dependency), used during
     /// coherence checking.
-    pub upstream: bool,
+    pub fn upstream(&self) -> bool {
+        self.get(Self::UPSTREAM)
+    }
+
+    pub fn set_upstream(&mut self, value: bool) {
+        self.set(Self::UPSTREAM, value)
+    }
 
     /// A fundamental trait is a trait where adding an impl for an existing type
     /// is considered a breaking change. Examples of fundamental traits are the
@@ -184,16 +833,47 @@ pub struct TraitFlags {
     /// As of this writing (2020-03-27), fundamental traits are declared by the
     /// unstable `#[fundamental]` attribute in rustc, and hence cannot appear
     /// outside of the standard library.
-    pub fundamental: bool,
+    pub fn fundamental(&self) -> bool {
+        self.get(Self::FUNDAMENTAL)
+    }
+
+    pub fn set_fundamental(&mut self, value: bool) {
+        self.set(Self::FUNDAMENTAL, value)
+    }
 
     /// Indicates that chalk cannot list all of the implementations of the given
     /// trait, likely because it is a publicly exported trait in a library.
     ///
     /// Currently (2020-03-27) rustc and rust-analyzer mark all traits as
     /// non_enumerable, and in the future it may become the only option.
-    pub non_enumerable: bool,
+    pub fn non_enumerable(&self) -> bool {
+        self.get(Self::NON_ENUMERABLE)
+    }
+
+    pub fn set_non_enumerable(&mut self, value: bool) {
+        self.set(Self::NON_ENUMERABLE, value)
+    }
+
+    pub fn coinductive(&self) -> bool {
+        self.get(Self::COINDUCTIVE)
+    }
 
-    pub coinductive: bool,
+    pub fn set_coinductive(&mut self, value: bool) {
+        self.set(Self::COINDUCTIVE, value)
+    }
+}
+
+impl std::fmt::Debug for TraitFlags {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fmt.debug_struct("TraitFlags")
+            .field("auto", &self.auto())
+            .field("marker", &self.marker())
+            .field("upstream", &self.upstream())
+            .field("fundamental", &self.fundamental())
+            .field("non_enumerable", &self.non_enumerable())
+            .field("coinductive", &self.coinductive())
+            .finish()
+    }
 }
 
 /// An inline bound, e.g. `: Foo<K>` in `impl<K, T: Foo<K>> SomeType<T>`.
@@ -244,6 +924,89 @@ impl<I: Interner> IntoWhereClauses<I> for QuantifiedInlineBound<I> {
     }
 }
 
+/// Renders a bound applied to some self type for use in diagnostics,
+/// e.g. `T: Foo<Bar>` or `<T as Iterator>::Item = u32`. Names are
+/// taken from the current program (via the interner's debug hooks)
+/// when available, otherwise the usual fallback debug output is used.
+pub trait DescribeBound<I: Interner> {
+    fn describe(&self, interner: &I, self_ty: Ty<I>) -> String;
+}
+
+impl<I: Interner> DescribeBound<I> for InlineBound<I> {
+    fn describe(&self, interner: &I, self_ty: Ty<I>) -> String {
+        match self {
+            InlineBound::TraitBound(b) => {
+                format!("{:?}", b.as_trait_ref(interner, self_ty).with_colon())
+            }
+            InlineBound::AliasEqBound(b) => {
+                let trait_ref = b.trait_bound.as_trait_ref(interner, self_ty);
+                let alias_eq = b.as_alias_eq(interner, &trait_ref);
+                format!("{:?} = {:?}", alias_eq.alias, alias_eq.ty)
+            }
+        }
+    }
+}
+
+impl<I: Interner> DescribeBound<I> for QuantifiedInlineBound<I> {
+    fn describe(&self, interner: &I, self_ty: Ty<I>) -> String {
+        let value = self.value.describe(interner, self_ty.shifted_in(interner));
+        if self.binders.is_empty() {
+            return value;
+        }
+        format!("forall<{}> {}", BinderKinds(&self.binders), value)
+    }
+}
+
+/// Displays a list of binders by kind, e.g. `type, lifetime`, as in
+/// the debug output of `Binders`.
+struct BinderKinds<'a>(&'a [ParameterKind<()>]);
+
+impl fmt::Display for BinderKinds<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, binder) in self.0.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            match binder {
+                ParameterKind::Ty(()) => write!(f, "type")?,
+                ParameterKind::Lifetime(()) => write!(f, "lifetime")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Renders a bound as it is written after the colon, e.g. `: Foo<K>`
+/// or `: Iterator<Item = u32>`. Names are taken from the current
+/// program when available, as with `DescribeBound`.
+impl<I: Interner> fmt::Display for InlineBound<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InlineBound::TraitBound(b) => write!(f, ": {}", b),
+            InlineBound::AliasEqBound(b) => write!(f, ": {}", b),
+        }
+    }
+}
+
+/// Renders a quantified bound like the `Display` impl of
+/// `InlineBound`, prefixed by its binders, e.g. `: forall<lifetime> Foo<^0.0>`.
+pub trait DisplayBound {
+    fn display_bound(&self) -> String;
+}
+
+impl<I: Interner> DisplayBound for QuantifiedInlineBound<I> {
+    fn display_bound(&self) -> String {
+        if self.binders.is_empty() {
+            return self.value.to_string();
+        }
+        let bound = match &self.value {
+            InlineBound::TraitBound(b) => b.to_string(),
+            InlineBound::AliasEqBound(b) => b.to_string(),
+        };
+        format!(": forall<{}> {}", BinderKinds(&self.binders), bound)
+    }
+}
+
 /// Represents a trait bound on e.g. a type or type parameter.
 /// Does not know anything about what it's binding.
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Fold)]
@@ -252,6 +1015,13 @@ pub struct TraitBound<I: Interner> {
     pub args_no_self: Vec<Parameter<I>>,
 }
 
+/// Renders the bound without the bound type, e.g. `Foo<K>`.
+impl<I: Interner> fmt::Display for TraitBound<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}{:?}", self.trait_id, Angle(&self.args_no_self))
+    }
+}
+
 impl<I: Interner> TraitBound<I> {
     fn into_where_clauses(&self, interner: &I, self_ty: Ty<I>) -> Vec<WhereClause<I>> {
         let trait_ref = self.as_trait_ref(interner, self_ty);
@@ -280,10 +1050,43 @@ pub struct AliasEqBound<I: Interner> {
     pub value: Ty<I>,
 }
 
+/// Renders the bound without the bound type, e.g. `Foo<K, Item = V>`.
+impl<I: Interner> fmt::Display for AliasEqBound<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}<", self.trait_bound.trait_id)?;
+        for arg in &self.trait_bound.args_no_self {
+            write!(f, "{:?}, ", arg)?;
+        }
+        write!(
+            f,
+            "{:?}{:?} = {:?}>",
+            AssocTypeName(self.associated_ty_id),
+            Angle(&self.parameters),
+            self.value
+        )
+    }
+}
+
+/// Debug-prints just the name of an associated type, e.g. `Item`.
+struct AssocTypeName<I: Interner>(AssocTypeId<I>);
+
+impl<I: Interner> fmt::Debug for AssocTypeName<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        I::debug_assoc_type_name(self.0, f).unwrap_or_else(|| write!(f, "{:?}", self.0))
+    }
+}
+
 impl<I: Interner> AliasEqBound<I> {
     fn into_where_clauses(&self, interner: &I, self_ty: Ty<I>) -> Vec<WhereClause<I>> {
         let trait_ref = self.trait_bound.as_trait_ref(interner, self_ty);
+        let alias_eq = self.as_alias_eq(interner, &trait_ref);
+        vec![
+            WhereClause::Implemented(trait_ref),
+            WhereClause::AliasEq(alias_eq),
+        ]
+    }
 
+    fn as_alias_eq(&self, interner: &I, trait_ref: &TraitRef<I>) -> AliasEq<I> {
         let substitution = Substitution::from(
             interner,
             self.parameters
@@ -292,16 +1095,33 @@ impl<I: Interner> AliasEqBound<I> {
                 .chain(trait_ref.substitution.iter(interner).cloned()),
         );
 
-        vec![
-            WhereClause::Implemented(trait_ref),
-            WhereClause::AliasEq(AliasEq {
-                alias: AliasTy {
-                    associated_ty_id: self.associated_ty_id,
-                    substitution,
-                },
-                ty: self.value.clone(),
+        AliasEq {
+            alias: AliasTy::Projection(ProjectionTy {
+                associated_ty_id: self.associated_ty_id,
+                substitution,
             }),
-        ]
+            ty: self.value.clone(),
+        }
+    }
+
+    /// Like `into_where_clauses`, but wraps each resulting where clause
+    /// in `outer_binders`. The bound itself is assumed to already live
+    /// inside those binders; `self_ty` is given relative to the
+    /// enclosing scope and is shifted in accordingly.
+    pub fn into_quantified_where_clauses(
+        &self,
+        interner: &I,
+        self_ty: Ty<I>,
+        outer_binders: Vec<ParameterKind<()>>,
+    ) -> Vec<QuantifiedWhereClause<I>> {
+        let self_ty = self_ty.shifted_in(interner);
+        self.into_where_clauses(interner, self_ty)
+            .into_iter()
+            .map(|wc| Binders {
+                binders: outer_binders.clone(),
+                value: wc,
+            })
+            .collect()
     }
 }
 
@@ -319,6 +1139,24 @@ impl<T> Anonymize for [ParameterKind<T>] {
     }
 }
 
+/// Parameters tracked by name are anonymized in key order, so the
+/// result does not depend on the order they were inserted in.
+impl<T> Anonymize for BTreeMap<String, ParameterKind<T>> {
+    fn anonymize(&self) -> Vec<ParameterKind<()>> {
+        self.values().map(|pk| pk.map_ref(|_| ())).collect()
+    }
+}
+
+/// True if the two lists contain the same where clauses, ignoring
+/// order and duplicates. Clauses are compared structurally, so the
+/// lists must be expressed under the same binders.
+pub fn where_clauses_equivalent<I: Interner>(
+    a: &[QuantifiedWhereClause<I>],
+    b: &[QuantifiedWhereClause<I>],
+) -> bool {
+    a.iter().all(|wc| b.contains(wc)) && b.iter().all(|wc| a.contains(wc))
+}
+
 pub trait ToParameter {
     /// Utility for converting a list of all the binders into scope
     /// into references to those binders. Simply pair the binders with
@@ -343,8 +1181,23 @@ impl<'a> ToParameter for (&'a ParameterKind<()>, usize) {
         debruijn: DebruijnIndex,
     ) -> Parameter<I> {
         let &(binder, index) = self;
-        let bound_var = BoundVar::new(debruijn, index);
-        match *binder {
+        binder
+            .map_ref(|_| index)
+            .to_parameter_at_depth(interner, debruijn)
+    }
+}
+
+/// A binder whose index has already been paired with its kind, so
+/// that e.g. `ParameterKind::Ty(2)` refers to the type bound at
+/// index 2.
+impl ToParameter for ParameterKind<usize> {
+    fn to_parameter_at_depth<I: Interner>(
+        &self,
+        interner: &I,
+        debruijn: DebruijnIndex,
+    ) -> Parameter<I> {
+        let bound_var = BoundVar::new(debruijn, self.into_inner());
+        match *self {
             ParameterKind::Lifetime(_) => LifetimeData::BoundVar(bound_var)
                 .intern(interner)
                 .cast(interner),
@@ -412,20 +1265,15 @@ impl<I: Interner> AssociatedTyDatum<I> {
     /// these quantified where clauses are in the scope of the
     /// `binders` field.
     pub fn bounds_on_self(&self, interner: &I) -> Vec<QuantifiedWhereClause<I>> {
-        let Binders { binders, value } = &self.binders;
-
         // Create a list `P0...Pn` of references to the binders in
         // scope for this associated type:
-        let substitution = Substitution::from(
-            interner,
-            binders.iter().zip(0..).map(|p| p.to_parameter(interner)),
-        );
+        let substitution = Substitution::from(interner, self.binders.enumerate_params(interner));
 
         // The self type will be `<P0 as Foo<P1..Pn>>::Item<Pn..Pm>` etc
-        let self_ty = TyData::Alias(AliasTy {
+        let self_ty = ProjectionTy {
             associated_ty_id: self.id,
             substitution,
-        })
+        }
         .intern(interner);
 
         // Now use that as the self type for the bounds, transforming
@@ -434,7 +1282,8 @@ impl<I: Interner> AssociatedTyDatum<I> {
         // ```
         // <P0 as Foo<P1..Pn>>::Item<Pn..Pm>: Debug
         // ```
-        value
+        self.binders
+            .value
             .bounds
             .iter()
             .flat_map(|b| b.into_where_clauses(interner, self_ty.clone()))
@@ -442,6 +1291,45 @@ impl<I: Interner> AssociatedTyDatum<I> {
     }
 }
 
+/// An opaque type such as the return type of
+///
+/// ```notrust
+/// fn foo<'a, T>(x: &'a T) -> impl Iterator<Item = &'a u32> + 'a { .. }
+/// ```
+///
+/// The binders are the generic parameters of the enclosing function
+/// that the opaque type may refer to, lifetimes included.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct OpaqueTyDatum<I: Interner> {
+    pub opaque_ty_id: OpaqueTyId<I>,
+    pub bound: Binders<OpaqueTyDatumBound<I>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Fold, HasInterner)]
+pub struct OpaqueTyDatumBound<I: Interner> {
+    /// The type the opaque type stands for, as inferred from the
+    /// function body.
+    pub hidden_ty: Ty<I>,
+
+    /// The bounds written on the opaque type (`Iterator<Item = ..>`
+    /// above), which the hidden type must satisfy.
+    pub bounds: Vec<QuantifiedInlineBound<I>>,
+}
+
+impl<I: Interner> OpaqueTyDatum<I> {
+    /// Returns the opaque type's bounds applied to the hidden type,
+    /// e.g. `Implemented(Vec<T>: Iterator)`. As with
+    /// `AssociatedTyDatum::bounds_on_self`, these are in the scope of
+    /// the `bound` binders.
+    pub fn bounds_on_self(&self, interner: &I) -> Vec<QuantifiedWhereClause<I>> {
+        let OpaqueTyDatumBound { hidden_ty, bounds } = &self.bound.value;
+        bounds
+            .iter()
+            .flat_map(|b| b.into_where_clauses(interner, hidden_ty.clone()))
+            .collect()
+    }
+}
+
 /// Represents the *value* of an associated type that is assigned
 /// from within some impl.
 ///
@@ -476,9 +1364,10 @@ pub struct AssociatedTyValue<I: Interner> {
     /// ```
     pub associated_ty_id: AssocTypeId<I>,
 
-    /// Additional binders declared on the associated type itself,
-    /// beyond those from the impl. This would be empty for normal
-    /// associated types, but non-empty for generic associated types.
+    /// The value, under binders for all the generics in scope: those
+    /// declared on the associated type itself (empty for normal
+    /// associated types, non-empty for generic associated types),
+    /// followed by those of the impl.
     ///
     /// ```ignore
     /// impl<T> Iterable for Vec<T> {
@@ -486,15 +1375,77 @@ pub struct AssociatedTyValue<I: Interner> {
     ///           // ^^^^ refers to these generics here
     /// }
     /// ```
+    ///
+    /// Here the binders are `['a, T]`.
     pub value: Binders<AssociatedTyValueBound<I>>,
 }
 
+impl<I: Interner> AssociatedTyValue<I> {
+    /// All the generic parameters in scope for the value type, in the
+    /// order the value's bound variables refer to them: the associated
+    /// type's own parameters first, then the impl's parameters.
+    pub fn all_binders(&self) -> &[ParameterKind<()>] {
+        &self.value.binders
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Fold, HasInterner)]
 pub struct AssociatedTyValueBound<I: Interner> {
     /// Type that we normalize to. The X in `type Foo<'a> = X`.
     pub ty: Ty<I>,
 }
 
+/// Represents an associated const declaration found inside of a trait:
+///
+/// ```notrust
+/// trait Foo<P1..Pn> { // P0 is Self
+///     const BAR: Ty;
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AssociatedConstDatum<I: Interner> {
+    /// The trait this associated const is defined in.
+    pub trait_id: TraitId<I>,
+
+    /// The ID of this associated const.
+    pub id: AssocConstId<I>,
+
+    /// Name of this associated const.
+    pub name: I::Identifier,
+
+    /// The type of the const, under binders for the trait's
+    /// parameters `P0..Pn`.
+    pub ty: Binders<Ty<I>>,
+
+    /// Whether the trait gives the const a default value, in which
+    /// case impls need not provide one.
+    pub has_default: bool,
+}
+
+/// Represents the *value* of an associated const that is assigned
+/// from within some impl.
+///
+/// ```ignore
+/// impl Foo for Bar {
+///     const BAR: Ty = XXX; // <-- represents this line!
+/// }
+/// ```
+///
+/// Chalk does not reason about the values of consts, so only the
+/// type the impl gives is recorded.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AssociatedConstValue<I: Interner> {
+    /// Impl in which this associated const value is found.
+    pub impl_id: ImplId<I>,
+
+    /// Associated const being defined.
+    pub associated_const_id: AssocConstId<I>,
+
+    /// The type of the const, under binders for the impl's
+    /// parameters.
+    pub ty: Binders<Ty<I>>,
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
 pub enum Polarity {
     Positive,
@@ -2,18 +2,79 @@ use self::builder::ClauseBuilder;
 use self::env_elaborator::elaborate_env_clauses;
 use self::program_clauses::ToProgramClauses;
 use crate::split::Split;
+use crate::wf;
 use crate::RustIrDatabase;
-use chalk_ir::cast::Cast;
+use chalk_ir::cast::{Cast, Caster};
 use chalk_ir::could_match::CouldMatch;
 use chalk_ir::interner::Interner;
 use chalk_ir::*;
+use chalk_rust_ir::{LangItem, TraitDatum};
 use rustc_hash::FxHashSet;
+use std::iter;
 
 pub mod builder;
 mod builtin_traits;
 mod env_elaborator;
 pub mod program_clauses;
 
+pub use self::builtin_traits::{builtin_impls_for, push_program_clauses_for_struct_sized};
+
+/// Pushes the rule saying that `trait_ref` may hold because of an impl
+/// an upstream crate could compatibly add in the future. Only
+/// non-`#[fundamental]` traits get this rule. Must be called from
+/// within the trait's binders, so that `trait_ref` and `where_clauses`
+/// are in scope.
+///
+/// Upstream crates may compatibly add impls of their traits for their
+/// own types, so for an ordinary trait we generate:
+///
+/// ```notrust
+/// // For `trait Foo<T> where Self: Eq<T> { ... }`
+/// forall<Self, T> {
+///     Implemented(Self: Foo<T>) :-
+///         Implemented(Self: Eq<T>), // where clauses
+///         Compatible,               // compatible modality
+///         IsUpstream(Self),
+///         IsUpstream(T),
+///         CannotProve.              // returns ambiguous
+/// }
+/// ```
+///
+/// Fundamental traits promise not to do that, which is what allows
+/// negative reasoning about them, so for those we generate nothing.
+/// The orphan rules themselves are the same for fundamental and
+/// ordinary traits.
+pub fn push_clauses_for_compatible_upstream_impls<I: Interner>(
+    builder: &mut ClauseBuilder<'_, I>,
+    trait_datum: &TraitDatum<I>,
+    trait_ref: &TraitRef<I>,
+    where_clauses: &[QuantifiedWhereClause<I>],
+) {
+    if trait_datum.flags.fundamental() {
+        debug!(
+            "push_clauses_for_compatible_upstream_impls: {:?} is fundamental",
+            trait_datum.id
+        );
+        return;
+    }
+
+    let interner = builder.interner();
+    builder.push_clause(
+        trait_ref.clone(),
+        where_clauses
+            .iter()
+            .cloned()
+            .casted(interner)
+            .chain(iter::once(DomainGoal::Compatible(()).cast(interner)))
+            .chain(
+                trait_ref
+                    .type_parameters(interner)
+                    .map(|ty| DomainGoal::IsUpstream(ty).cast(interner)),
+            )
+            .chain(iter::once(GoalData::CannotProve(()).intern(interner))),
+    );
+}
+
 /// For auto-traits, we generate a default rule for every struct,
 /// unless there is a manual impl for that struct given explicitly.
 ///
@@ -71,6 +132,7 @@ pub fn push_auto_trait_impls<I: Interner>(
         return;
     }
 
+    let phantom_data = builder.db.lang_item_struct(LangItem::PhantomData);
     let binders = struct_datum.binders.map_ref(|b| &b.fields);
     let interner = builder.interner();
     builder.push_binders(&binders, |builder, fields| {
@@ -86,6 +148,22 @@ pub fn push_auto_trait_impls<I: Interner>(
             substitution: Substitution::from1(interner, self_ty),
         };
 
+        // `PhantomData<T>` has no fields, but it is treated as though
+        // it owned a `T`, so its conditions are its type parameters.
+        let component_tys: Vec<Ty<I>> = if Some(struct_id) == phantom_data {
+            builder
+                .placeholders_in_scope()
+                .iter()
+                .filter_map(|p| p.ty(interner))
+                .cloned()
+                .collect()
+        } else {
+            fields
+                .iter()
+                .map(|field_ty| phantom_data_contents(interner, phantom_data, field_ty))
+                .collect()
+        };
+
         // forall<P0..Pn> { // generic parameters from struct
         //   MyStruct<...>: MyAutoTrait :-
         //      Field0: MyAutoTrait,
@@ -94,14 +172,43 @@ pub fn push_auto_trait_impls<I: Interner>(
         // }
         builder.push_clause(
             auto_trait_ref,
-            fields.iter().map(|field_ty| TraitRef {
+            component_tys.into_iter().map(|ty| TraitRef {
                 trait_id: auto_trait_id,
-                substitution: Substitution::from1(interner, field_ty.clone()),
+                substitution: Substitution::from1(interner, ty),
             }),
         );
     });
 }
 
+/// For auto traits, a field of type `PhantomData<T>` stands for a `T`.
+/// Returns `T` for such fields (looking through nested `PhantomData`)
+/// and the field type itself otherwise.
+fn phantom_data_contents<I: Interner>(
+    interner: &I,
+    phantom_data: Option<StructId<I>>,
+    field_ty: &Ty<I>,
+) -> Ty<I> {
+    let mut ty = field_ty;
+    while let TyData::Apply(ApplicationTy {
+        name: TypeName::Struct(struct_id),
+        substitution,
+    }) = ty.data(interner)
+    {
+        if Some(*struct_id) != phantom_data {
+            break;
+        }
+        match substitution
+            .parameters(interner)
+            .first()
+            .and_then(|p| p.ty(interner))
+        {
+            Some(inner) => ty = inner,
+            None => break,
+        }
+    }
+    ty.clone()
+}
+
 /// Given some goal `goal` that must be proven, along with
 /// its `environment`, figures out the program clauses that apply
 /// to this goal from the Rust program. So for example if the goal
@@ -247,14 +354,24 @@ fn program_clauses_that_could_match<I: Interner>(
                 );
             }
         }
-        DomainGoal::Holds(WhereClause::AliasEq(alias_predicate)) => {
-            db.associated_ty_data(alias_predicate.alias.associated_ty_id)
-                .to_program_clauses(builder);
-        }
+        DomainGoal::Holds(WhereClause::AliasEq(alias_predicate)) => match &alias_predicate.alias {
+            AliasTy::Projection(projection) => db
+                .associated_ty_data(projection.associated_ty_id)
+                .to_program_clauses(builder),
+            // No clauses are produced for opaque types yet.
+            AliasTy::Opaque(_) => (),
+        },
         DomainGoal::WellFormed(WellFormed::Trait(trait_predicate)) => {
             db.trait_datum(trait_predicate.trait_id)
                 .to_program_clauses(builder);
         }
+        DomainGoal::WellFormed(WellFormed::Impl(impl_id)) => {
+            let impl_datum = db.impl_datum(*impl_id);
+            builder.push_clause(goal.clone(), wf::impl_wf_goals(db, &impl_datum));
+        }
+        DomainGoal::WellFormed(WellFormed::Struct(struct_id)) => {
+            builder.push_clause(goal.clone(), iter::once(wf::struct_wf_goal(db, *struct_id)));
+        }
         DomainGoal::WellFormed(WellFormed::Ty(ty))
         | DomainGoal::IsUpstream(ty)
         | DomainGoal::DownstreamType(ty) => match_ty(builder, environment, ty),
@@ -274,14 +391,16 @@ fn program_clauses_that_could_match<I: Interner>(
             //     type Item = Bar; // <-- associated type value
             // }
             // ```
-            let associated_ty_datum = db.associated_ty_data(alias.associated_ty_id);
-            let trait_id = associated_ty_datum.trait_id;
-            let trait_parameters = db.trait_parameters_from_projection(alias);
-            push_program_clauses_for_associated_type_values_in_impls_of(
-                builder,
-                trait_id,
-                trait_parameters,
-            );
+            if let AliasTy::Projection(projection) = alias {
+                let associated_ty_datum = db.associated_ty_data(projection.associated_ty_id);
+                let trait_id = associated_ty_datum.trait_id;
+                let trait_parameters = db.trait_parameters_from_projection(projection);
+                push_program_clauses_for_associated_type_values_in_impls_of(
+                    builder,
+                    trait_id,
+                    trait_parameters,
+                );
+            }
         }
         DomainGoal::LocalImplAllowed(trait_ref) => db
             .trait_datum(trait_ref.trait_id)
@@ -290,6 +409,33 @@ fn program_clauses_that_could_match<I: Interner>(
     };
 }
 
+/// Returns the positive impls whose header could match `trait_ref`,
+/// i.e., the candidates a solve for `trait_ref` would consider. This
+/// is an over-approximation: it only looks at the impl headers, not
+/// at their where clauses, so some of the returned impls may not
+/// actually apply.
+pub fn relevant_impls<I: Interner>(
+    trait_ref: &TraitRef<I>,
+    db: &dyn RustIrDatabase<I>,
+) -> Vec<ImplId<I>> {
+    let interner = db.interner();
+    db.impls_for_trait(
+        trait_ref.trait_id,
+        trait_ref.substitution.parameters(interner),
+    )
+    .into_iter()
+    .filter(|&impl_id| {
+        let impl_datum = db.impl_datum(impl_id);
+        impl_datum.is_positive()
+            && impl_datum
+                .binders
+                .value
+                .trait_ref
+                .could_match(interner, trait_ref)
+    })
+    .collect()
+}
+
 /// Generate program clauses from the associated-type values
 /// found in impls of the given trait. i.e., if `trait_id` = Iterator,
 /// then we would generate program clauses from each `type Item = ...`
@@ -355,10 +501,11 @@ fn match_ty<I: Interner>(
         TyData::Placeholder(_) => {
             builder.push_clause(WellFormed::Ty(ty.clone()), Some(FromEnv::Ty(ty.clone())));
         }
-        TyData::Alias(alias_ty) => builder
+        TyData::Alias(AliasTy::Projection(projection)) => builder
             .db
-            .associated_ty_data(alias_ty.associated_ty_id)
+            .associated_ty_data(projection.associated_ty_id)
             .to_program_clauses(builder),
+        TyData::Alias(AliasTy::Opaque(_)) => (),
         TyData::Function(quantified_ty) => {
             builder.push_fact(WellFormed::Ty(ty.clone()));
             quantified_ty
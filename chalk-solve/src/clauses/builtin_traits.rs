@@ -1,9 +1,11 @@
 use super::builder::ClauseBuilder;
 use crate::{Interner, RustIrDatabase, TraitRef, WellKnownTrait};
-use chalk_ir::TyData;
+use chalk_ir::{ApplicationTy, Ty, TyData, TypeName};
 
 mod sized;
 
+pub use sized::push_program_clauses_for_struct_sized;
+
 /// For well known traits we have special hard-coded impls, either as an
 /// optimization or to enforce special rules for correctness.
 pub fn add_builtin_program_clauses<I: Interner>(
@@ -21,5 +23,48 @@ pub fn add_builtin_program_clauses<I: Interner>(
         WellKnownTrait::SizedTrait => sized::add_sized_program_clauses(db, builder, trait_ref, ty),
         WellKnownTrait::CopyTrait => { /* TODO */ }
         WellKnownTrait::CloneTrait => { /* TODO */ }
+        // `Drop` impls are written by the user; no type implements it
+        // implicitly.
+        WellKnownTrait::DropTrait => {}
     }
 }
+
+/// Returns the well-known traits that `ty` is known to implement
+/// without running the solver.
+///
+/// The answer is partial: it only covers impls forced by the database
+/// (see `RustIrDatabase::force_impl_for`) and `Sized` for structs
+/// without fields. A trait missing from the result may still be
+/// implemented -- e.g. `Sized` for a struct whose fields are all
+/// `Sized`, or `Copy` through a user impl -- and finding that out
+/// requires a real query.
+pub fn builtin_impls_for<I: Interner>(
+    ty: &Ty<I>,
+    db: &dyn RustIrDatabase<I>,
+) -> Vec<WellKnownTrait> {
+    let interner = db.interner();
+    let ty = ty.data(interner);
+    [
+        WellKnownTrait::SizedTrait,
+        WellKnownTrait::CopyTrait,
+        WellKnownTrait::CloneTrait,
+    ]
+    .iter()
+    .cloned()
+    .filter(|&well_known| {
+        if db.force_impl_for(well_known, ty) {
+            return true;
+        }
+        match (well_known, ty) {
+            (
+                WellKnownTrait::SizedTrait,
+                TyData::Apply(ApplicationTy {
+                    name: TypeName::Struct(struct_id),
+                    ..
+                }),
+            ) => db.struct_datum(*struct_id).field_count() == 0,
+            _ => false,
+        }
+    })
+    .collect()
+}
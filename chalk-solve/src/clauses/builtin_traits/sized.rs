@@ -2,7 +2,7 @@ use std::iter;
 
 use crate::clauses::ClauseBuilder;
 use crate::{Interner, RustIrDatabase, TraitRef};
-use chalk_ir::{ApplicationTy, Substitution, TyData, TypeName};
+use chalk_ir::{ApplicationTy, StructId, Substitution, TyData, TypeName};
 
 pub fn add_sized_program_clauses<I: Interner>(
     db: &dyn RustIrDatabase<I>,
@@ -10,8 +10,6 @@ pub fn add_sized_program_clauses<I: Interner>(
     trait_ref: &TraitRef<I>,
     ty: &TyData<I>,
 ) {
-    let interner = db.interner();
-
     let (struct_id, substitution) = match ty {
         TyData::Apply(ApplicationTy {
             name: TypeName::Struct(struct_id),
@@ -22,10 +20,27 @@ pub fn add_sized_program_clauses<I: Interner>(
         _ => return,
     };
 
+    push_program_clauses_for_struct_sized(db, builder, trait_ref, struct_id, substitution);
+}
+
+/// Pushes the clauses under which the struct `struct_id<substitution>`
+/// is `Sized`:
+///
+/// - a struct with no fields is always `Sized`;
+/// - otherwise the struct is `Sized` iff its last field is, so a
+///   struct whose last field is unsized is not `Sized` either.
+pub fn push_program_clauses_for_struct_sized<I: Interner>(
+    db: &dyn RustIrDatabase<I>,
+    builder: &mut ClauseBuilder<'_, I>,
+    trait_ref: &TraitRef<I>,
+    struct_id: StructId<I>,
+    substitution: &Substitution<I>,
+) {
+    let interner = db.interner();
     let struct_datum = db.struct_datum(struct_id);
 
     // Structs with no fields are always Sized
-    if struct_datum.binders.map_ref(|b| b.fields.is_empty()).value {
+    if struct_datum.field_count() == 0 {
         builder.push_fact(trait_ref.clone());
         return;
     }
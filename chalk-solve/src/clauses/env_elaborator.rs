@@ -1,6 +1,7 @@
 use super::program_clauses::ToProgramClauses;
 use crate::clauses::builder::ClauseBuilder;
 use crate::clauses::match_type_name;
+use crate::ext::TraitDatumExt;
 use crate::DomainGoal;
 use crate::FromEnv;
 use crate::ProgramClause;
@@ -46,9 +47,14 @@ impl<'me, I: Interner> EnvElaborator<'me, I> {
 
     fn visit_alias_ty(&mut self, alias_ty: &AliasTy<I>) {
         debug!("EnvElaborator::visit_alias_ty(alias_ty={:?})", alias_ty);
-        self.db
-            .associated_ty_data(alias_ty.associated_ty_id)
-            .to_program_clauses(&mut self.builder);
+        match alias_ty {
+            AliasTy::Projection(projection) => self
+                .db
+                .associated_ty_data(projection.associated_ty_id)
+                .to_program_clauses(&mut self.builder),
+            // There is no datum for opaque types to elaborate yet.
+            AliasTy::Opaque(_) => (),
+        }
     }
 
     fn visit_ty(&mut self, ty: &Ty<I>) {
@@ -83,10 +89,8 @@ impl<'me, I: Interner> EnvElaborator<'me, I> {
                 // If we know that `T: Iterator`, then we also know
                 // things about `<T as Iterator>::Item`, so push those
                 // implied bounds too:
-                for &associated_ty_id in &trait_datum.associated_ty_ids {
-                    self.db
-                        .associated_ty_data(associated_ty_id)
-                        .to_program_clauses(&mut self.builder);
+                for associated_ty_datum in trait_datum.associated_ty_data(self.db) {
+                    associated_ty_datum.to_program_clauses(&mut self.builder);
                 }
             }
             FromEnv::Ty(ty) => self.visit_ty(ty),
@@ -109,7 +109,7 @@ impl<I: Interner> ToProgramClauses<I> for AssociatedTyValue<I> {
             // ```
             builder.push_clause(
                 Normalize {
-                    alias: projection.clone(),
+                    alias: AliasTy::Projection(projection.clone()),
                     ty: assoc_ty_value.ty,
                 },
                 impl_where_clauses.chain(assoc_ty_where_clauses),
@@ -435,7 +435,7 @@ impl<I: Interner> ToProgramClauses<I> for TraitDatum<I> {
             }
 
             // Orphan rules:
-            if !self.flags.upstream {
+            if !self.flags.upstream() {
                 // Impls for traits declared locally always pass the impl rules
                 builder.push_fact(DomainGoal::LocalImplAllowed(trait_ref.clone()));
             } else {
@@ -450,24 +450,12 @@ impl<I: Interner> ToProgramClauses<I> for TraitDatum<I> {
                 }
             }
 
-            // Fundamental traits can be reasoned about negatively without any ambiguity, so no
-            // need for this rule if the trait is fundamental.
-            if !self.flags.fundamental {
-                builder.push_clause(
-                    trait_ref.clone(),
-                    where_clauses
-                        .iter()
-                        .cloned()
-                        .casted(interner)
-                        .chain(iter::once(DomainGoal::Compatible(()).cast(interner)))
-                        .chain(
-                            trait_ref
-                                .type_parameters(interner)
-                                .map(|ty| DomainGoal::IsUpstream(ty).cast(interner)),
-                        )
-                        .chain(iter::once(GoalData::CannotProve(()).intern(interner))),
-                );
-            }
+            super::push_clauses_for_compatible_upstream_impls(
+                builder,
+                self,
+                &trait_ref,
+                &where_clauses,
+            );
 
             // Reverse implied bound rules: given (e.g.) `trait Foo: Bar + Baz`,
             // we create rules like:
@@ -575,14 +563,14 @@ impl<I: Interner> ToProgramClauses<I> for AssociatedTyDatum<I> {
         builder.push_binders(&binders, |builder, (where_clauses, bounds)| {
             let substitution = builder.substitution_in_scope();
 
-            let alias = AliasTy {
+            let projection = ProjectionTy {
                 associated_ty_id: self.id,
                 substitution: substitution.clone(),
             };
-            let projection_ty = alias.clone().intern(interner);
+            let projection_ty = projection.clone().intern(interner);
 
             // Retrieve the trait ref embedding the associated type
-            let trait_ref = builder.db.trait_ref_from_projection(&alias);
+            let trait_ref = builder.db.trait_ref_from_projection(&projection);
 
             // Construct an application from the projection. So if we have `<T as Iterator>::Item`,
             // we would produce `(Iterator::Item)<T>`.
@@ -592,6 +580,7 @@ impl<I: Interner> ToProgramClauses<I> for AssociatedTyDatum<I> {
             }
             .intern(interner);
 
+            let alias = AliasTy::Projection(projection);
             let alias_eq = AliasEq {
                 alias: alias.clone(),
                 ty: app_ty.clone(),
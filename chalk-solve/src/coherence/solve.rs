@@ -15,7 +15,7 @@ impl<I: Interner> CoherenceSolver<'_, I> {
     ) -> Result<(), CoherenceError<I>> {
         // Ignore impls for marker traits as they are allowed to overlap.
         let trait_datum = self.db.trait_datum(self.trait_id);
-        if trait_datum.flags.marker {
+        if trait_datum.is_marker_trait() {
             return Ok(());
         }
 
@@ -47,6 +47,75 @@ impl<I: Interner> CoherenceSolver<'_, I> {
         Ok(())
     }
 
+    /// Among the positive impls of the trait that apply to
+    /// `trait_ref`, returns the one that specializes all of the
+    /// others. Returns `None` if no impl applies, or if no single
+    /// impl is strictly more specialized than every other applicable
+    /// impl (i.e., the choice is ambiguous, as it can be for the
+    /// overlapping impls of a marker trait).
+    pub fn most_specialized_impl(&self, trait_ref: &TraitRef<I>) -> Option<ImplId<I>> {
+        debug_heading!("most_specialized_impl(trait_ref={:?})", trait_ref);
+        assert_eq!(trait_ref.trait_id, self.trait_id);
+
+        let interner = self.db.interner();
+        let candidates: Vec<_> = self
+            .db
+            .impls_for_trait(self.trait_id, trait_ref.substitution.parameters(interner))
+            .into_iter()
+            .filter(|&impl_id| {
+                self.db.impl_datum(impl_id).is_positive() && self.applies(impl_id, trait_ref)
+            })
+            .collect();
+        debug!("most_specialized_impl: candidates = {:?}", candidates);
+
+        candidates.iter().copied().find(|&more_special_id| {
+            candidates
+                .iter()
+                .filter(|&&less_special_id| less_special_id != more_special_id)
+                .all(|&less_special_id| {
+                    self.specializes(less_special_id, more_special_id)
+                        && !self.specializes(more_special_id, less_special_id)
+                })
+        })
+    }
+
+    // Creates the goal `exists<P0..Pn> { T0 = U0, ..., Tm = Um, WC }` for the impl
+    // `impl<P0..Pn> SomeTrait<T1..Tm> for T0 where WC` and the trait ref
+    // `U0: SomeTrait<U1..Um>`, and tests whether it has a unique solution.
+    fn applies(&self, impl_id: ImplId<I>, trait_ref: &TraitRef<I>) -> bool {
+        let interner = self.db.interner();
+        let impl_datum = &self.db.impl_datum(impl_id);
+
+        let gb = &mut GoalBuilder::new(self.db);
+        let goal = gb.exists(
+            &impl_datum.binders,
+            trait_ref,
+            |gb, _, impl_bound, trait_ref| {
+                let interner = gb.interner();
+                let params_goals = impl_bound
+                    .trait_ref
+                    .substitution
+                    .parameters(interner)
+                    .iter()
+                    .cloned()
+                    .zip(trait_ref.substitution.parameters(interner).iter().cloned())
+                    .map(|(a, b)| GoalData::EqGoal(EqGoal { a, b }).intern(interner));
+                let wc_goals = impl_bound.where_clauses.iter().cloned().casted(interner);
+                gb.all(params_goals.chain(wc_goals))
+            },
+        );
+
+        let canonical_goal = &goal.into_closed_goal(interner);
+        match self
+            .solver_choice
+            .into_solver()
+            .solve(self.db, canonical_goal)
+        {
+            Some(sol) => sol.is_unique(),
+            None => false,
+        }
+    }
+
     // Test if the set of types that these two impls apply to overlap. If the test succeeds, these
     // two impls are disjoint.
     //
@@ -2,6 +2,14 @@ use crate::RustIrDatabase;
 use chalk_ir::interner::Interner;
 use chalk_ir::*;
 
+/// True if cycles involving `T: Trait` goals for the given trait
+/// must be treated coinductively -- that is, if the trait is an auto
+/// trait or was explicitly declared `#[coinductive]`.
+pub fn requires_coinduction<I: Interner>(trait_id: TraitId<I>, db: &dyn RustIrDatabase<I>) -> bool {
+    let trait_datum = db.trait_datum(trait_id);
+    trait_datum.is_auto_trait() || trait_datum.is_coinductive_trait()
+}
+
 pub trait IsCoinductive<I: Interner> {
     /// A goal G has coinductive semantics if proving G is allowed to
     /// assume G is true (very roughly speaking). In the case of
@@ -19,10 +27,7 @@ impl<I: Interner> IsCoinductive<I> for Goal<I> {
         let interner = db.interner();
         match self.data(interner) {
             GoalData::DomainGoal(DomainGoal::Holds(wca)) => match wca {
-                WhereClause::Implemented(tr) => {
-                    db.trait_datum(tr.trait_id).is_auto_trait()
-                        || db.trait_datum(tr.trait_id).is_coinductive_trait()
-                }
+                WhereClause::Implemented(tr) => requires_coinduction(tr.trait_id, db),
                 WhereClause::AliasEq(..) => false,
             },
             GoalData::DomainGoal(DomainGoal::WellFormed(WellFormed::Trait(..))) => true,
@@ -0,0 +1,60 @@
+use crate::RustIrDatabase;
+use chalk_ir::fold::shift::Shift;
+use chalk_ir::interner::Interner;
+use chalk_ir::*;
+use rustc_hash::FxHashSet;
+
+/// Expands a list of where clauses with the where clauses that they
+/// imply. For example, given `trait Sub where Self: Super { }`, a
+/// `T: Sub` clause also yields `T: Super`, and so on transitively.
+///
+/// Only implied bounds that are not themselves higher-ranked (like
+/// `forall<'a> Self: Foo<'a>`) are added, since the result is a list
+/// of plain where clauses.
+pub struct ElaborateBound<'db, I: Interner> {
+    db: &'db dyn RustIrDatabase<I>,
+}
+
+impl<'db, I: Interner> ElaborateBound<'db, I> {
+    pub fn new(db: &'db dyn RustIrDatabase<I>) -> Self {
+        ElaborateBound { db }
+    }
+
+    /// Returns the given where clauses followed by the ones they
+    /// imply, with duplicates removed.
+    pub fn elaborate(
+        &self,
+        where_clauses: impl IntoIterator<Item = WhereClause<I>>,
+    ) -> Vec<WhereClause<I>> {
+        let interner = self.db.interner();
+        let mut visited = FxHashSet::default();
+        let mut result = vec![];
+        let mut stack: Vec<_> = where_clauses.into_iter().collect();
+        stack.reverse();
+
+        while let Some(where_clause) = stack.pop() {
+            if !visited.insert(where_clause.clone()) {
+                continue;
+            }
+
+            if let WhereClause::Implemented(trait_ref) = &where_clause {
+                let trait_datum = self.db.trait_datum(trait_ref.trait_id);
+                let implied = trait_datum
+                    .binders
+                    .map_ref(|b| &b.where_clauses)
+                    .substitute(interner, &trait_ref.substitution);
+                stack.extend(
+                    implied
+                        .into_iter()
+                        .rev()
+                        .filter(|qwc| qwc.binders.is_empty())
+                        .filter_map(|qwc| qwc.value.shifted_out(interner).ok()),
+                );
+            }
+
+            result.push(where_clause);
+        }
+
+        result
+    }
+}
@@ -1,7 +1,11 @@
+use crate::clauses::builder::ClauseBuilder;
 use crate::infer::InferenceTable;
+use crate::RustIrDatabase;
 use chalk_ir::fold::Fold;
 use chalk_ir::interner::{HasInterner, Interner};
 use chalk_ir::*;
+use chalk_rust_ir::{AssociatedTyDatum, ImplDatum, TraitDatum};
+use std::sync::Arc;
 
 pub trait CanonicalExt<T: HasInterner, I: Interner> {
     fn map<OP, U>(self, interner: &I, op: OP) -> Canonical<U::Result>
@@ -109,3 +113,83 @@ impl<I: Interner> GoalExt<I> for Goal<I> {
         infer.u_canonicalize(interner, &canonical_goal).quantified
     }
 }
+
+pub trait TraitDatumExt<I: Interner> {
+    fn associated_ty_data(&self, db: &dyn RustIrDatabase<I>) -> Vec<Arc<AssociatedTyDatum<I>>>;
+}
+
+impl<I: Interner> TraitDatumExt<I> for TraitDatum<I> {
+    /// Loads the declarations of all the trait's associated types, in
+    /// the same order as `associated_ty_ids`.
+    fn associated_ty_data(&self, db: &dyn RustIrDatabase<I>) -> Vec<Arc<AssociatedTyDatum<I>>> {
+        self.associated_ty_ids
+            .iter()
+            .map(|&id| db.associated_ty_data(id))
+            .collect()
+    }
+}
+
+pub trait ImplDatumExt<I: Interner> {
+    fn full_wf_goals(&self, db: &dyn RustIrDatabase<I>) -> Vec<Goal<I>>;
+
+    fn env_clauses(&self, db: &dyn RustIrDatabase<I>) -> Vec<ProgramClause<I>>;
+}
+
+impl<I: Interner> ImplDatumExt<I> for ImplDatum<I> {
+    /// Returns the goals that must be proven for this impl to be
+    /// well-formed, as checked by `WfSolver::verify_trait_impl`.
+    fn full_wf_goals(&self, db: &dyn RustIrDatabase<I>) -> Vec<Goal<I>> {
+        crate::wf::impl_wf_goals(db, self)
+    }
+
+    /// Returns the reverse implied bound rules of the trait,
+    /// instantiated with this impl's header. That is, given
+    /// `trait Foo where WC` and `impl<P0..Pn> Foo for T`, returns
+    /// `forall<P0..Pn> { FromEnv(WC) :- FromEnv(T: Foo) }` for each
+    /// where clause in `WC`, which lets the impl body assume the
+    /// trait's where clauses.
+    fn env_clauses(&self, db: &dyn RustIrDatabase<I>) -> Vec<ProgramClause<I>> {
+        let interner = db.interner();
+        let trait_datum = db.trait_datum(self.trait_id());
+        let mut clauses = vec![];
+        let builder = &mut ClauseBuilder::new(db, &mut clauses);
+        builder.push_binders(&self.binders, |builder, impl_datum_bound| {
+            let trait_ref = impl_datum_bound.trait_ref;
+            let where_clauses = trait_datum
+                .binders
+                .map_ref(|b| &b.where_clauses)
+                .substitute(interner, &trait_ref.substitution);
+            for qwc in &where_clauses {
+                builder.push_binders(qwc, |builder, wc| {
+                    builder.push_clause(
+                        wc.into_from_env_goal(interner),
+                        Some(trait_ref.clone().from_env()),
+                    );
+                });
+            }
+        });
+        clauses
+    }
+}
+
+pub trait TypeNameExt<I: Interner> {
+    fn is_local(&self, db: &dyn RustIrDatabase<I>) -> bool;
+}
+
+impl<I: Interner> TypeNameExt<I> for TypeName<I> {
+    /// True if the type is declared in the local crate, i.e. is not
+    /// `#[upstream]`; an associated type is local if its trait is.
+    /// Only the declaration is consulted, so this is false for an
+    /// upstream `#[fundamental]` type like `Box` even if `IsLocal`
+    /// holds for `Box<Local>`.
+    fn is_local(&self, db: &dyn RustIrDatabase<I>) -> bool {
+        match self {
+            TypeName::Struct(struct_id) => !db.struct_datum(*struct_id).flags.upstream,
+            TypeName::AssociatedType(assoc_ty_id) => {
+                let trait_id = db.associated_ty_data(*assoc_ty_id).trait_id;
+                !db.trait_datum(trait_id).flags.upstream()
+            }
+            TypeName::Error => false,
+        }
+    }
+}
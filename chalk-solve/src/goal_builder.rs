@@ -3,7 +3,6 @@ use cast::CastTo;
 use chalk_ir::cast::Cast;
 use chalk_ir::cast::Caster;
 use chalk_ir::*;
-use chalk_rust_ir::ToParameter;
 use fold::shift::Shift;
 use fold::Fold;
 use interner::{HasInterner, Interner};
@@ -133,14 +132,8 @@ impl<'i, I: Interner> GoalBuilder<'i, I> {
         // actually an identity mapping, since this `forall` will be the innermost
         // debruijn binder and so forth, so there's no actual reason to
         // *do* the substitution, since it would effectively just be a clone.
-        let substitution: Substitution<I> = Substitution::from(
-            interner,
-            binders
-                .binders
-                .iter()
-                .zip(0..)
-                .map(|p| p.to_parameter(interner)),
-        );
+        let substitution: Substitution<I> =
+            Substitution::from(interner, binders.enumerate_params(interner));
 
         // Shift passthru into one level of binder, to account for the `forall<P0..Pn>`
         // we are about to introduce.
@@ -2,6 +2,7 @@ use chalk_ir::interner::Interner;
 use chalk_ir::*;
 use chalk_ir::{cast::Cast, fold::Fold};
 
+mod ambiguity;
 pub(crate) mod canonicalize;
 pub(crate) mod instantiate;
 mod invert;
@@ -134,7 +135,7 @@ impl<I: Interner> InferenceTable<I> {
         match self.unify.probe_value(var) {
             InferenceValue::Unbound(_) => None,
             InferenceValue::Bound(ref val) => {
-                let ty = val.as_ref(interner).ty().unwrap().clone();
+                let ty = val.as_ref(interner).unwrap_ty().clone();
                 assert!(!ty.needs_shift(interner));
                 Some(ty)
             }
@@ -173,7 +174,7 @@ impl<I: Interner> InferenceTable<I> {
     fn probe_ty_var(&mut self, interner: &I, var: EnaVariable<I>) -> Option<Ty<I>> {
         match self.unify.probe_value(var) {
             InferenceValue::Unbound(_) => None,
-            InferenceValue::Bound(ref val) => Some(val.as_ref(interner).ty().unwrap().clone()),
+            InferenceValue::Bound(ref val) => Some(val.as_ref(interner).unwrap_ty().clone()),
         }
     }
 
@@ -187,9 +188,7 @@ impl<I: Interner> InferenceTable<I> {
     fn probe_lifetime_var(&mut self, interner: &I, var: EnaVariable<I>) -> Option<Lifetime<I>> {
         match self.unify.probe_value(var) {
             InferenceValue::Unbound(_) => None,
-            InferenceValue::Bound(ref val) => {
-                Some(val.as_ref(interner).lifetime().unwrap().clone())
-            }
+            InferenceValue::Bound(ref val) => Some(val.as_ref(interner).unwrap_lifetime().clone()),
         }
     }
 
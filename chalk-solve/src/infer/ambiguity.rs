@@ -0,0 +1,100 @@
+use chalk_engine::context::Floundered;
+use chalk_engine::fallible::*;
+use chalk_ir::fold::{Fold, Folder};
+use chalk_ir::interner::Interner;
+use chalk_ir::*;
+
+use super::{EnaVariable, InferenceTable};
+
+impl<I: Interner> InferenceTable<I> {
+    /// Returns true if `value` still refers to an inference variable
+    /// that has not been bound to a value, looking through the values
+    /// of variables that have been. A solution for such a goal should
+    /// be reported as ambiguous rather than as having no solution,
+    /// since some (as yet unknown) instantiation may still apply.
+    pub(crate) fn check_for_ambiguity<T: Fold<I>>(&mut self, interner: &I, value: &T) -> bool {
+        let mut finder = UnresolvedVarFinder {
+            table: self,
+            interner,
+            found: false,
+        };
+        value
+            .fold_with(&mut finder, DebruijnIndex::INNERMOST)
+            .unwrap();
+        finder.found
+    }
+
+    /// Returns `Err(Floundered)` if `value` still refers to an unbound
+    /// inference variable (see `check_for_ambiguity`). Goals whose
+    /// answer depends on the values of such variables, like negative
+    /// goals, must be deferred until they are bound.
+    pub(crate) fn flounder_if_unbound<T: Fold<I>>(
+        &mut self,
+        interner: &I,
+        value: &T,
+    ) -> Result<(), Floundered> {
+        if self.check_for_ambiguity(interner, value) {
+            Err(Floundered)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Walks a value, following the bindings of inference variables, and
+/// records whether any unbound variable was found. The folded value
+/// itself is discarded.
+struct UnresolvedVarFinder<'table, 'i, I: Interner> {
+    table: &'table mut InferenceTable<I>,
+    interner: &'i I,
+    found: bool,
+}
+
+impl<'i, I: Interner> Folder<'i, I> for UnresolvedVarFinder<'_, 'i, I>
+where
+    I: 'i,
+{
+    fn as_dyn(&mut self) -> &mut dyn Folder<'i, I> {
+        self
+    }
+
+    fn fold_inference_ty(
+        &mut self,
+        var: InferenceVar,
+        _outer_binder: DebruijnIndex,
+    ) -> Fallible<Ty<I>> {
+        let interner = self.interner;
+        let var = EnaVariable::from(var);
+        match self.table.probe_ty_var(interner, var) {
+            Some(ty) => {
+                ty.fold_with(self, DebruijnIndex::INNERMOST)?;
+            }
+            None => self.found = true,
+        }
+        Ok(var.to_ty(interner))
+    }
+
+    fn fold_inference_lifetime(
+        &mut self,
+        var: InferenceVar,
+        _outer_binder: DebruijnIndex,
+    ) -> Fallible<Lifetime<I>> {
+        let interner = self.interner;
+        let var = EnaVariable::from(var);
+        match self.table.probe_lifetime_var(interner, var) {
+            Some(l) => {
+                l.fold_with(self, DebruijnIndex::INNERMOST)?;
+            }
+            None => self.found = true,
+        }
+        Ok(var.to_lifetime(interner))
+    }
+
+    fn interner(&self) -> &'i I {
+        self.interner
+    }
+
+    fn target_interner(&self) -> &'i I {
+        self.interner()
+    }
+}
@@ -3,7 +3,6 @@ use chalk_ir::fold::shift::Shift;
 use chalk_ir::fold::{Fold, Folder};
 use chalk_ir::interner::{HasInterner, Interner};
 use chalk_ir::*;
-use std::cmp::max;
 
 use super::{EnaVariable, InferenceTable, ParameterEnaVariable};
 
@@ -35,20 +34,15 @@ impl<I: Interner> InferenceTable<I> {
         let mut q = Canonicalizer {
             table: self,
             free_vars: Vec::new(),
-            max_universe: UniverseIndex::root(),
             interner,
         };
         let value = value.fold_with(&mut q, DebruijnIndex::INNERMOST).unwrap();
-        let free_vars = q.free_vars.clone();
-        let max_universe = q.max_universe;
 
         Canonicalized {
             quantified: Canonical {
                 value,
                 binders: q.into_binders(),
             },
-            max_universe,
-            free_vars,
         }
     }
 }
@@ -57,19 +51,11 @@ impl<I: Interner> InferenceTable<I> {
 pub(crate) struct Canonicalized<T: HasInterner> {
     /// The canonicalized result.
     pub(crate) quantified: Canonical<T>,
-
-    /// The free existential variables, along with the universes they inhabit.
-    pub(crate) free_vars: Vec<ParameterEnaVariable<T::Interner>>,
-
-    /// The maximum universe of any universally quantified variables
-    /// encountered.
-    max_universe: UniverseIndex,
 }
 
 struct Canonicalizer<'q, I: Interner> {
     table: &'q mut InferenceTable<I>,
     free_vars: Vec<ParameterEnaVariable<I>>,
-    max_universe: UniverseIndex,
     interner: &'q I,
 }
 
@@ -104,26 +90,6 @@ where
         self
     }
 
-    fn fold_free_placeholder_ty(
-        &mut self,
-        universe: PlaceholderIndex,
-        _outer_binder: DebruijnIndex,
-    ) -> Fallible<Ty<I>> {
-        let interner = self.interner;
-        self.max_universe = max(self.max_universe, universe.ui);
-        Ok(universe.to_ty(interner))
-    }
-
-    fn fold_free_placeholder_lifetime(
-        &mut self,
-        universe: PlaceholderIndex,
-        _outer_binder: DebruijnIndex,
-    ) -> Fallible<Lifetime<I>> {
-        let interner = self.interner;
-        self.max_universe = max(self.max_universe, universe.ui);
-        Ok(universe.to_lifetime(interner))
-    }
-
     fn forbid_free_vars(&self) -> bool {
         true
     }
@@ -76,16 +76,10 @@ impl<I: Interner> InferenceTable<I> {
     where
         T: Fold<I, Result = T> + HasInterner<Interner = I>,
     {
-        let Canonicalized {
-            free_vars,
-            quantified,
-            ..
-        } = self.canonicalize(interner, &value);
-
         // If the original contains free existential variables, give up.
-        if !free_vars.is_empty() {
-            return None;
-        }
+        self.flounder_if_unbound(interner, value).ok()?;
+
+        let Canonicalized { quantified, .. } = self.canonicalize(interner, &value);
 
         // If this contains free universal variables, replace them with existentials.
         assert!(quantified.binders.is_empty());
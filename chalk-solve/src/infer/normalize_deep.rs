@@ -52,6 +52,9 @@ where
         let interner = self.interner;
         let var = EnaVariable::from(var);
         match self.table.probe_ty_var(interner, var) {
+            // A value without inference variables is already fully
+            // resolved, so skip rebuilding it.
+            Some(ty) if !ty.contains_inference_var(interner) => Ok(ty.shifted_in(interner)),
             Some(ty) => Ok(ty
                 .fold_with(self, DebruijnIndex::INNERMOST)?
                 .shifted_in(interner)), // FIXME shift
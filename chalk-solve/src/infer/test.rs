@@ -300,3 +300,334 @@ fn lifetime_constraint_indirect() {
         "InEnvironment { environment: Env([]), goal: \'?2 == \'!1_0 }",
     );
 }
+
+#[test]
+fn check_for_ambiguity() {
+    let interner = &ChalkIr;
+    let mut table: InferenceTable<ChalkIr> = InferenceTable::new();
+    let environment0 = Environment::new(interner);
+    let a = table.new_variable(U0).to_ty(interner);
+    let b = table.new_variable(U0).to_ty(interner);
+    let goal = ty!(apply (item 0) (expr a));
+    assert!(table.check_for_ambiguity(interner, &goal));
+
+    // `a` is now bound, but its value still mentions the unbound `b`.
+    table
+        .unify(interner, &environment0, &a, &ty!(apply (item 1) (expr b)))
+        .unwrap();
+    assert!(table.check_for_ambiguity(interner, &goal));
+
+    table
+        .unify(interner, &environment0, &b, &ty!(apply (item 2)))
+        .unwrap();
+    assert!(!table.check_for_ambiguity(interner, &goal));
+}
+
+#[test]
+fn flounder_if_unbound() {
+    let interner = &ChalkIr;
+    let mut table: InferenceTable<ChalkIr> = InferenceTable::new();
+    let environment0 = Environment::new(interner);
+    let a = table.new_variable(U0).to_ty(interner);
+    let b = table.new_variable(U0).to_ty(interner);
+    let ty = ty!(apply (item 0) (expr a));
+
+    assert!(table.flounder_if_unbound(interner, &ty).is_err());
+
+    table
+        .unify(interner, &environment0, &a, &ty!(apply (item 1) (expr b)))
+        .unwrap();
+    assert!(table.flounder_if_unbound(interner, &ty).is_err());
+
+    table
+        .unify(interner, &environment0, &b, &ty!(apply (item 2)))
+        .unwrap();
+    assert!(table.flounder_if_unbound(interner, &ty).is_ok());
+}
+
+#[test]
+fn substitution_concat_split_at() {
+    let interner = &ChalkIr;
+    let subst = Substitution::from(
+        interner,
+        vec![
+            ty!(apply (item 0)),
+            ty!(apply (item 1)),
+            ty!(apply (item 2)),
+        ],
+    );
+    for n in 0..=subst.len(interner) {
+        let (prefix, suffix) = subst.split_at(n, interner);
+        assert_eq!(prefix.len(interner), n);
+        assert_eq!(prefix.concat(suffix, interner), subst);
+    }
+}
+
+#[test]
+fn contains() {
+    let interner = &ChalkIr;
+    let plain = ty!(apply (item 0) (apply (item 1)));
+    assert!(!plain.contains_alias(interner));
+    assert!(!plain.contains_placeholder(interner));
+    assert!(!plain.contains_inference_var(interner));
+
+    let alias = ty!(apply (item 0) (alias (item 1) (apply (item 2))));
+    assert!(alias.contains_alias(interner));
+    assert!(!alias.contains_placeholder(interner));
+
+    let placeholder = ty!(apply (item 0) (placeholder 1));
+    assert!(placeholder.contains_placeholder(interner));
+    assert!(!placeholder.contains_inference_var(interner));
+
+    let infer = ty!(apply (item 0) (infer 0));
+    assert!(infer.contains_inference_var(interner));
+    assert!(!infer.contains_alias(interner));
+
+    // Lifetimes are searched too, including under a `for<'a>` binder.
+    let lifetimes = ty!(function 1 (apply (item 0) (lifetime (bound 0)) (lifetime (infer 0))));
+    assert!(lifetimes.contains_inference_var(interner));
+    assert!(!lifetimes.contains_placeholder(interner));
+}
+
+#[test]
+fn ty_eq_agrees_with_hash() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::collections::HashSet;
+    use std::hash::{Hash, Hasher};
+
+    fn hash_of(ty: &Ty<ChalkIr>) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        ty.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    let a = ty!(apply (item 0) (apply (item 1)));
+    let b = ty!(apply (item 0) (apply (item 1)));
+    let c = ty!(apply (item 0) (apply (item 2)));
+    assert_eq!(a, b);
+    assert_eq!(hash_of(&a), hash_of(&b));
+    assert_ne!(a, c);
+
+    let set: HashSet<_> = vec![a, b, c].into_iter().collect();
+    assert_eq!(set.len(), 2);
+}
+
+#[test]
+fn term_eq() {
+    let interner = &ChalkIr;
+    let term_eq = |a: &Ty<ChalkIr>, b: &Ty<ChalkIr>| interner.term_eq(a.interned(), b.interned());
+
+    let nested = ty!(apply (item 0) (apply (item 1) (placeholder 2)));
+    assert!(term_eq(
+        &nested,
+        &ty!(apply (item 0) (apply (item 1) (placeholder 2)))
+    ));
+    assert!(!term_eq(
+        &nested,
+        &ty!(apply (item 0) (apply (item 1) (placeholder 3)))
+    ));
+    assert!(!term_eq(
+        &nested,
+        &ty!(apply (item 0) (apply (item 2) (placeholder 2)))
+    ));
+
+    let function = ty!(function 1 (apply (item 0) (bound 1 0)));
+    assert!(term_eq(
+        &function,
+        &ty!(function 1 (apply (item 0) (bound 1 0)))
+    ));
+    assert!(!term_eq(
+        &function,
+        &ty!(function 2 (apply (item 0) (bound 1 0)))
+    ));
+    assert!(!term_eq(
+        &function,
+        &ty!(function 1 (apply (item 0) (bound 0)))
+    ));
+
+    let alias = ty!(alias (item 0) (apply (item 1)));
+    assert!(term_eq(&alias, &ty!(alias (item 0) (apply (item 1)))));
+    assert!(!term_eq(&alias, &ty!(alias (item 0) (apply (item 2)))));
+}
+
+#[test]
+fn eq_ignoring_self_type() {
+    use chalk_ir::interner::RawId;
+
+    let interner = &ChalkIr;
+    let trait_ref = |trait_index, parameters: Vec<Ty<ChalkIr>>| TraitRef {
+        trait_id: TraitId(RawId { index: trait_index }),
+        substitution: Substitution::from(interner, parameters),
+    };
+
+    let foo_u32 = trait_ref(0, vec![ty!(placeholder 0), ty!(apply (item 1))]);
+    assert!(foo_u32.eq_ignoring_self_type(
+        &trait_ref(0, vec![ty!(placeholder 1), ty!(apply (item 1))]),
+        interner
+    ));
+    assert!(!foo_u32.eq_ignoring_self_type(
+        &trait_ref(0, vec![ty!(placeholder 0), ty!(apply (item 2))]),
+        interner
+    ));
+    assert!(!foo_u32.eq_ignoring_self_type(
+        &trait_ref(1, vec![ty!(placeholder 0), ty!(apply (item 1))]),
+        interner
+    ));
+}
+
+#[test]
+fn where_clause_self_ty() {
+    use chalk_ir::interner::RawId;
+
+    let interner = &ChalkIr;
+    // `?0: Foo<'!1, u32>`: the self type comes first, ahead of the
+    // trait's own lifetime and type parameters.
+    let implemented = WhereClause::Implemented(TraitRef {
+        trait_id: TraitId(RawId { index: 0 }),
+        substitution: Substitution::from(
+            interner,
+            vec![
+                arg!((infer 0)),
+                arg!((lifetime (placeholder 1))),
+                arg!((apply (item 1))),
+            ],
+        ),
+    });
+    assert_eq!(implemented.self_ty(interner), Some(ty!(infer 0)));
+
+    let alias_eq = WhereClause::AliasEq(AliasEq {
+        alias: AliasTy::Projection(ProjectionTy {
+            associated_ty_id: AssocTypeId(RawId { index: 0 }),
+            substitution: Substitution::from1(interner, ty!(infer 0)),
+        }),
+        ty: ty!(apply (item 1)),
+    });
+    assert_eq!(alias_eq.self_ty(interner), None);
+}
+
+#[test]
+fn is_closed_alias() {
+    let interner = &ChalkIr;
+    let is_closed = |ty: Ty<ChalkIr>| match ty.data(interner) {
+        TyData::Alias(alias) => alias.is_closed_alias(interner),
+        _ => panic!("expected an alias, found `{:?}`", ty),
+    };
+
+    assert!(is_closed(
+        ty!(alias (item 0) (apply (item 1)) (placeholder 1))
+    ));
+    // Variables bound by a binder within the alias are not free.
+    assert!(is_closed(ty!(alias (item 0) (function 1 (bound 0)))));
+
+    assert!(!is_closed(ty!(alias (item 0) (apply (item 1) (infer 0)))));
+    assert!(!is_closed(ty!(alias (item 0) (bound 0))));
+    assert!(!is_closed(ty!(alias (item 0) (function 1 (bound 1 0)))));
+}
+
+#[test]
+fn as_projection_and_opaque_ty() {
+    let interner = &ChalkIr;
+    let projection = ty!(alias (item 0) (apply (item 1)));
+    let opaque = ty!(opaque (item 0) (apply (item 1)));
+    let plain = ty!(apply (item 0));
+
+    assert!(projection.as_projection(interner).is_some());
+    assert!(projection.as_opaque_ty(interner).is_none());
+
+    // Both are aliases, but an opaque type is not a projection.
+    assert!(opaque.is_alias(interner));
+    assert!(opaque.as_projection(interner).is_none());
+    assert!(opaque.as_opaque_ty(interner).is_some());
+
+    assert!(plain.as_projection(interner).is_none());
+    assert!(plain.as_opaque_ty(interner).is_none());
+}
+
+#[test]
+fn substitute_bound_var() {
+    let interner = &ChalkIr;
+    let ty = ty!(apply (item 0) (bound 0) (bound 1)
+                 (function 1 (apply (item 1) (bound 1 0) (bound 0))));
+    let replacement = ty!(apply (item 2) (bound 2));
+
+    // Within the `function` binder, `^0.0` is written `^1.0`, and the
+    // replacement's own bound variable is shifted in to match.
+    assert_eq!(
+        ty.substitute_bound_var(0, DebruijnIndex::INNERMOST, &replacement, interner),
+        ty!(apply (item 0) (apply (item 2) (bound 2)) (bound 1)
+            (function 1 (apply (item 1) (apply (item 2) (bound 1 2)) (bound 0))))
+    );
+}
+
+#[test]
+fn free_lifetime_variables() {
+    let interner = &ChalkIr;
+    // `Foo<'^0.0, for<'a> fn(Bar<'a, '^0.1, '^0.0>)>`
+    let ty = ty!(apply (item 0) (lifetime (bound 0))
+                 (function 1 (apply (item 1)
+                     (lifetime (bound 0)) (lifetime (bound 1 1)) (lifetime (bound 1 0)))));
+
+    // The lifetime bound by `for<'a>` is skipped; the escaping ones
+    // are reported relative to the top of the type, once each.
+    assert_eq!(
+        ty.free_lifetime_variables(interner),
+        vec![lifetime!(bound 0), lifetime!(bound 1)]
+    );
+}
+
+#[test]
+fn max_universe() {
+    let interner = &ChalkIr;
+    assert_eq!(
+        ty!(placeholder 2).universe_of_placeholder(interner),
+        Some(UniverseIndex { counter: 2 })
+    );
+    assert_eq!(
+        ty!(apply (item 0) (placeholder 2)).universe_of_placeholder(interner),
+        None
+    );
+
+    assert_eq!(
+        ty!(apply (item 0)).max_universe(interner),
+        UniverseIndex::ROOT
+    );
+    assert_eq!(
+        ty!(apply (item 0) (placeholder 1) (apply (item 1) (placeholder 3)) (placeholder 2))
+            .max_universe(interner),
+        UniverseIndex { counter: 3 }
+    );
+    assert_eq!(
+        ty!(apply (item 0) (placeholder 1) (lifetime (placeholder 4))).max_universe(interner),
+        UniverseIndex { counter: 4 }
+    );
+}
+
+#[test]
+fn quantified_goals_shift_escaping_vars() {
+    use chalk_ir::goals;
+
+    let interner = &ChalkIr;
+    let eq_goal = |a: Ty<ChalkIr>, b: Ty<ChalkIr>| {
+        GoalData::EqGoal(EqGoal {
+            a: a.cast(interner),
+            b: b.cast(interner),
+        })
+        .intern(interner)
+    };
+
+    // `^0.0` is bound outside of the new binder, so within it the
+    // variable is written `^1.0`.
+    let goal = eq_goal(ty!(bound 0), ty!(apply (item 0)));
+    let shifted = eq_goal(ty!(bound 1 0), ty!(apply (item 0)));
+    let binders = vec![ParameterKind::Ty(())];
+    assert_eq!(
+        goals::for_all(binders.clone(), goal.clone(), interner),
+        shifted
+            .clone()
+            .quantify(interner, QuantifierKind::ForAll, binders.clone())
+    );
+    assert_eq!(
+        goals::exists(binders.clone(), goal, interner),
+        shifted.quantify(interner, QuantifierKind::Exists, binders)
+    );
+}
@@ -34,7 +34,7 @@ use std::u32;
 ///     variable via the enum variant. However, we should always know
 ///     the kind of the variable from context, and hence we typically
 ///     "downcast" the resulting variable using
-///     e.g. `value.ty().unwrap()`.
+///     e.g. `value.unwrap_ty()`.
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub(crate) struct EnaVariable<I: Interner> {
     var: InferenceVar,
@@ -12,9 +12,11 @@ extern crate chalk_macros;
 pub mod clauses;
 pub mod coherence;
 mod coinductive_goal;
+pub mod elaborate;
 pub mod ext;
 pub mod goal_builder;
 mod infer;
+pub mod normalize;
 mod solve;
 pub mod split;
 pub mod wf;
@@ -27,6 +29,9 @@ pub trait RustIrDatabase<I: Interner>: Debug {
     /// Returns the datum for the associated type with the given id.
     fn associated_ty_data(&self, ty: AssocTypeId<I>) -> Arc<AssociatedTyDatum<I>>;
 
+    /// Returns the datum for the associated const with the given id.
+    fn associated_const_data(&self, id: AssocConstId<I>) -> Arc<AssociatedConstDatum<I>>;
+
     /// Returns the datum for the definition with the given id.
     fn trait_datum(&self, trait_id: TraitId<I>) -> Arc<TraitDatum<I>>;
 
@@ -39,6 +44,10 @@ pub trait RustIrDatabase<I: Interner>: Debug {
     /// Returns the `AssociatedTyValue` with the given id.
     fn associated_ty_value(&self, id: AssociatedTyValueId<I>) -> Arc<AssociatedTyValue<I>>;
 
+    /// Returns the `AssociatedConstValue` with the given id.
+    fn associated_const_value(&self, id: AssociatedConstValueId<I>)
+        -> Arc<AssociatedConstValue<I>>;
+
     /// If `id` is a struct id, returns `Some(id)` (but cast to `StructId`).
     fn as_struct_id(&self, id: &TypeName<I>) -> Option<StructId<I>>;
 
@@ -76,12 +85,26 @@ pub trait RustIrDatabase<I: Interner>: Debug {
         false
     }
 
+    /// Looks up a trait by its name. This is mainly intended for tests
+    /// and interactive use; by default, no names are known.
+    #[allow(unused_variables)]
+    fn trait_datum_by_name(&self, name: &I::Identifier) -> Option<Arc<TraitDatum<I>>> {
+        None
+    }
+
     /// Returns id of a trait lang item, if found
     fn well_known_trait_id(&self, well_known_trait: WellKnownTrait) -> TraitId<I>;
 
+    /// Returns the struct that implements the given lang item, if any.
+    #[allow(unused_variables)]
+    fn lang_item_struct(&self, lang_item: LangItem) -> Option<StructId<I>> {
+        None
+    }
+
     fn interner(&self) -> &I;
 }
 
+pub use coinductive_goal::requires_coinduction;
 pub use solve::Guidance;
 pub use solve::Solution;
 pub use solve::Solver;
@@ -0,0 +1,206 @@
+use crate::infer::InferenceTable;
+use crate::split::Split;
+use crate::RustIrDatabase;
+use chalk_engine::fallible::*;
+use chalk_ir::fold::shift::Shift;
+use chalk_ir::fold::{Fold, Folder, SuperFold};
+use chalk_ir::interner::Interner;
+use chalk_ir::*;
+
+/// Replaces every projection in `ty` with a fresh variable, returning
+/// the rewritten type together with the equalities that relate each
+/// variable to the projection it replaced. So `Vec<<T as Foo>::Item>`
+/// becomes `Vec<X>` with the constraint `AliasEq(<T as Foo>::Item = X)`.
+///
+/// The fresh variables are introduced by a new binder, so both the
+/// returned type and the constraints are only valid *inside* that
+/// binder. It binds one type variable per constraint, with `^0.i`
+/// standing for the projection in the `i`th constraint. Usually
+/// callers instantiate it existentially.
+///
+/// Projections that refer to variables bound within `ty` itself (e.g.
+/// `for<'a> fn(<&'a T as Foo>::Item)`) cannot be lifted out of their
+/// binder and are left in place.
+pub fn lift_projections<I: Interner>(ty: &Ty<I>, interner: &I) -> (Ty<I>, Vec<WhereClause<I>>) {
+    let mut lifter = ProjectionLifter {
+        interner,
+        constraints: vec![],
+    };
+    let ty = ty
+        .shifted_in(interner)
+        .fold_with(&mut lifter, DebruijnIndex::INNERMOST)
+        .expect("lifting projections cannot fail");
+    (ty, lifter.constraints)
+}
+
+struct ProjectionLifter<'i, I: Interner> {
+    interner: &'i I,
+    constraints: Vec<WhereClause<I>>,
+}
+
+impl<'i, I: Interner> Folder<'i, I> for ProjectionLifter<'i, I> {
+    fn as_dyn(&mut self) -> &mut dyn Folder<'i, I> {
+        self
+    }
+
+    fn fold_ty(&mut self, ty: &Ty<I>, outer_binder: DebruijnIndex) -> Fallible<Ty<I>> {
+        let interner = self.interner;
+        if let Some(alias) = ty.as_projection(interner) {
+            // Only lift the projection if it doesn't mention anything
+            // bound by the binders we've traversed so far.
+            if let Ok(projection) = alias.shifted_out_to(interner, outer_binder) {
+                let var = BoundVar::new(DebruijnIndex::INNERMOST, self.constraints.len());
+                self.constraints.push(WhereClause::AliasEq(AliasEq {
+                    alias: AliasTy::Projection(projection),
+                    ty: var.to_ty(interner),
+                }));
+                return Ok(var.shifted_in_from(outer_binder).to_ty(interner));
+            }
+        }
+        ty.super_fold_with(self.as_dyn(), outer_binder)
+    }
+
+    fn interner(&self) -> &'i I {
+        self.interner
+    }
+
+    fn target_interner(&self) -> &'i I {
+        self.interner()
+    }
+}
+
+/// Replaces each projection in `value` with the associated type value
+/// from the impl that provides it, so `Vec<<u32 as Foo>::Bar>`
+/// becomes `Vec<Baz>` given `impl Foo for u32 { type Bar = Baz; }`.
+/// The replacement is itself normalized in turn.
+///
+/// This is a purely syntactic lookup, not a query to the solver, so
+/// a projection is only replaced if it is closed (see
+/// `AliasTy::is_closed_alias`) and exactly one positive impl's header
+/// matches it, and that impl has no where clauses. Any other
+/// projection is left as it is.
+pub fn eagerly_normalize<I, T>(value: &T, db: &dyn RustIrDatabase<I>) -> T::Result
+where
+    I: Interner,
+    T: Fold<I>,
+{
+    let mut normalizer = EagerNormalizer {
+        db,
+        in_progress: vec![],
+    };
+    value
+        .fold_with(&mut normalizer, DebruijnIndex::INNERMOST)
+        .expect("eager normalization cannot fail")
+}
+
+struct EagerNormalizer<'i, I: Interner> {
+    db: &'i dyn RustIrDatabase<I>,
+
+    /// The projections whose normalized values are being folded,
+    /// so that a cycle like `type Bar = <u32 as Foo>::Bar` is not
+    /// followed forever.
+    in_progress: Vec<AliasTy<I>>,
+}
+
+impl<I: Interner> EagerNormalizer<'_, I> {
+    fn normalize(&self, alias: &AliasTy<I>) -> Option<Ty<I>> {
+        let db = self.db;
+        let interner = db.interner();
+        // Opaque types are never normalized eagerly: their hidden
+        // type is not visible to the caller.
+        let projection = match alias {
+            AliasTy::Projection(projection) => projection,
+            AliasTy::Opaque(_) => return None,
+        };
+        let (associated_ty_datum, trait_params, other_params) = db.split_projection(projection);
+
+        let mut matching_impls = db
+            .impls_for_trait(associated_ty_datum.trait_id, trait_params)
+            .into_iter()
+            .map(|impl_id| db.impl_datum(impl_id))
+            .filter(|impl_datum| impl_datum.is_positive())
+            .filter_map(|impl_datum| {
+                let mut table = InferenceTable::new();
+                let binders: Vec<_> = impl_datum
+                    .binders
+                    .binders
+                    .iter()
+                    .map(|pk| pk.map_ref(|_| UniverseIndex::ROOT))
+                    .collect();
+                let subst = table.fresh_subst(interner, &binders);
+                let trait_ref = subst.apply(&impl_datum.binders.value.trait_ref, interner);
+                let result = table
+                    .unify(
+                        interner,
+                        &Environment::new(interner),
+                        trait_ref.substitution.parameters(interner),
+                        trait_params,
+                    )
+                    .ok()?;
+                if !result.goals.is_empty() || !result.constraints.is_empty() {
+                    return None;
+                }
+                let impl_params = table.normalize_deep(interner, &subst);
+                if table.check_for_ambiguity(interner, &impl_params) {
+                    return None;
+                }
+                Some((impl_datum, impl_params))
+            });
+
+        let (impl_datum, impl_params) = matching_impls.next()?;
+        if matching_impls.next().is_some() || !impl_datum.binders.value.where_clauses.is_empty() {
+            return None;
+        }
+
+        let associated_ty_value = impl_datum
+            .associated_ty_value_ids
+            .iter()
+            .map(|&id| db.associated_ty_value(id))
+            .find(|value| value.associated_ty_id == projection.associated_ty_id)?;
+
+        // The value is bound by the associated type's own parameters
+        // followed by the impl's.
+        let parameters: Vec<_> = other_params
+            .iter()
+            .chain(impl_params.parameters(interner))
+            .cloned()
+            .collect();
+        Some(
+            associated_ty_value
+                .value
+                .substitute(interner, &parameters)
+                .ty,
+        )
+    }
+}
+
+impl<'i, I: Interner> Folder<'i, I> for EagerNormalizer<'i, I> {
+    fn as_dyn(&mut self) -> &mut dyn Folder<'i, I> {
+        self
+    }
+
+    fn fold_ty(&mut self, ty: &Ty<I>, outer_binder: DebruijnIndex) -> Fallible<Ty<I>> {
+        let interner = self.interner();
+        if let TyData::Alias(alias) = ty.data(interner) {
+            if alias.is_closed_alias(interner) && !self.in_progress.contains(alias) {
+                if let Some(normalized) = self.normalize(alias) {
+                    // `normalized` is closed, like `alias`, so it needs
+                    // no shifting to appear under `outer_binder`.
+                    self.in_progress.push(alias.clone());
+                    let result = normalized.fold_with(self.as_dyn(), outer_binder);
+                    self.in_progress.pop();
+                    return result;
+                }
+            }
+        }
+        ty.super_fold_with(self.as_dyn(), outer_binder)
+    }
+
+    fn interner(&self) -> &'i I {
+        self.db.interner()
+    }
+
+    fn target_interner(&self) -> &'i I {
+        self.interner()
+    }
+}
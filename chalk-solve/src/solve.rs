@@ -85,12 +85,24 @@ impl SolverChoice {
 
     /// Creates a solver state.
     pub fn into_solver<I: Interner>(self) -> Solver<I> {
+        self.into_solver_with_initial_clauses(vec![])
+    }
+
+    /// Creates a solver state in which `initial_clauses` hold in
+    /// every environment, in addition to the clauses of the program.
+    pub fn into_solver_with_initial_clauses<I: Interner>(
+        self,
+        initial_clauses: Vec<ProgramClause<I>>,
+    ) -> Solver<I> {
         match self {
             SolverChoice::SLG {
                 max_size,
                 expected_answers,
             } => Solver {
-                forest: Forest::new(SlgContext::new(max_size, expected_answers)),
+                forest: Forest::with_initial_clauses(
+                    SlgContext::new(max_size, expected_answers),
+                    initial_clauses,
+                ),
             },
         }
     }
@@ -201,6 +213,37 @@ impl<I: Interner> Solver<I> {
         let ops = self.forest.context().ops(program);
         self.forest.solve_multiple(&ops, goal, f)
     }
+
+    /// True if every goal in `goals` has a unique solution. Stops at
+    /// the first goal that does not.
+    pub fn prove_all(
+        &mut self,
+        program: &dyn RustIrDatabase<I>,
+        goals: &[UCanonical<InEnvironment<Goal<I>>>],
+    ) -> bool {
+        goals.iter().all(|goal| self.proves(program, goal))
+    }
+
+    /// True if some goal in `goals` has a unique solution. Stops at
+    /// the first goal that does.
+    pub fn prove_any(
+        &mut self,
+        program: &dyn RustIrDatabase<I>,
+        goals: &[UCanonical<InEnvironment<Goal<I>>>],
+    ) -> bool {
+        goals.iter().any(|goal| self.proves(program, goal))
+    }
+
+    fn proves(
+        &mut self,
+        program: &dyn RustIrDatabase<I>,
+        goal: &UCanonical<InEnvironment<Goal<I>>>,
+    ) -> bool {
+        match self.solve(program, goal) {
+            Some(sol) => sol.is_unique(),
+            None => false,
+        }
+    }
 }
 
 impl<I: Interner> std::fmt::Debug for Solver<I> {
@@ -620,21 +620,22 @@ impl<I: Interner> MayInvalidate<'_, I> {
     }
 
     fn aggregate_alias_tys(&mut self, new: &AliasTy<I>, current: &AliasTy<I>) -> bool {
-        let AliasTy {
-            associated_ty_id: new_name,
-            substitution: new_substitution,
-        } = new;
-        let AliasTy {
-            associated_ty_id: current_name,
-            substitution: current_substitution,
-        } = current;
-
-        self.aggregate_name_and_substs(
-            new_name,
-            new_substitution,
-            current_name,
-            current_substitution,
-        )
+        match (new, current) {
+            (AliasTy::Projection(new), AliasTy::Projection(current)) => self
+                .aggregate_name_and_substs(
+                    new.associated_ty_id,
+                    &new.substitution,
+                    current.associated_ty_id,
+                    &current.substitution,
+                ),
+            (AliasTy::Opaque(new), AliasTy::Opaque(current)) => self.aggregate_name_and_substs(
+                new.opaque_ty_id,
+                &new.substitution,
+                current.opaque_ty_id,
+                &current.substitution,
+            ),
+            _ => true,
+        }
     }
 
     fn aggregate_name_and_substs<N>(
@@ -295,24 +295,39 @@ impl<I: Interner> AntiUnifier<'_, '_, I> {
 
     fn aggregate_alias_tys(&mut self, alias1: &AliasTy<I>, alias2: &AliasTy<I>) -> Ty<I> {
         let interner = self.interner;
-        let AliasTy {
-            associated_ty_id: name1,
-            substitution: substitution1,
-        } = alias1;
-        let AliasTy {
-            associated_ty_id: name2,
-            substitution: substitution2,
-        } = alias2;
-
-        self.aggregate_name_and_substs(name1, substitution1, name2, substitution2)
-            .map(|(&associated_ty_id, substitution)| {
-                TyData::Alias(AliasTy {
-                    associated_ty_id,
-                    substitution,
+        match (alias1, alias2) {
+            (AliasTy::Projection(projection1), AliasTy::Projection(projection2)) => self
+                .aggregate_name_and_substs(
+                    &projection1.associated_ty_id,
+                    &projection1.substitution,
+                    &projection2.associated_ty_id,
+                    &projection2.substitution,
+                )
+                .map(|(&associated_ty_id, substitution)| {
+                    ProjectionTy {
+                        associated_ty_id,
+                        substitution,
+                    }
+                    .intern(interner)
                 })
-                .intern(interner)
-            })
-            .unwrap_or_else(|| self.new_variable())
+                .unwrap_or_else(|| self.new_variable()),
+            (AliasTy::Opaque(opaque1), AliasTy::Opaque(opaque2)) => self
+                .aggregate_name_and_substs(
+                    &opaque1.opaque_ty_id,
+                    &opaque1.substitution,
+                    &opaque2.opaque_ty_id,
+                    &opaque2.substitution,
+                )
+                .map(|(&opaque_ty_id, substitution)| {
+                    OpaqueTy {
+                        opaque_ty_id,
+                        substitution,
+                    }
+                    .intern(interner)
+                })
+                .unwrap_or_else(|| self.new_variable()),
+            _ => self.new_variable(),
+        }
     }
 
     fn aggregate_name_and_substs<N>(
@@ -15,6 +15,9 @@ use chalk_rust_ir::*;
 pub enum WfError<I: Interner> {
     IllFormedTypeDecl(chalk_ir::StructId<I>),
     IllFormedTraitImpl(chalk_ir::TraitId<I>),
+    IllFormedAssociatedTyValue(chalk_ir::AssocTypeId<I>),
+    MissingAssociatedConst(chalk_ir::TraitId<I>, I::Identifier),
+    IllTypedAssociatedConst(chalk_ir::TraitId<I>, I::Identifier),
 }
 
 impl<I: Interner> fmt::Display for WfError<I> {
@@ -30,6 +33,21 @@ impl<I: Interner> fmt::Display for WfError<I> {
                 "trait impl for `{:?}` does not meet well-formedness requirements",
                 id
             ),
+            WfError::IllFormedAssociatedTyValue(id) => write!(
+                f,
+                "value of associated type `{:?}` does not meet well-formedness requirements",
+                id
+            ),
+            WfError::MissingAssociatedConst(id, name) => write!(
+                f,
+                "trait impl for `{:?}` does not provide a value for associated const `{:?}`",
+                id, name
+            ),
+            WfError::IllTypedAssociatedConst(id, name) => write!(
+                f,
+                "trait impl for `{:?}` gives associated const `{:?}` a type other than the one declared by the trait",
+                id, name
+            ),
         }
     }
 }
@@ -99,7 +117,7 @@ impl<I: Interner> FoldInputTypes for Ty<I> {
 
             TyData::Alias(alias) => {
                 accumulator.push(self.clone());
-                alias.substitution.fold(interner, accumulator);
+                alias.substitution().fold(interner, accumulator);
             }
 
             TyData::Placeholder(_) => {
@@ -176,54 +194,8 @@ where
     pub fn verify_struct_decl(&self, struct_id: StructId<I>) -> Result<(), WfError<I>> {
         let interner = self.db.interner();
 
-        // Given a struct like
-        //
-        // ```rust
-        // struct Foo<T> where T: Eq {
-        //     data: Vec<T>
-        // }
-        // ```
-        let struct_datum = self.db.struct_datum(struct_id);
-
-        let mut gb = GoalBuilder::new(self.db);
-        let struct_data = struct_datum
-            .binders
-            .map_ref(|b| (&b.fields, &b.where_clauses));
-
-        // We make a goal like...
-        //
-        // forall<T> { ... }
-        let wg_goal = gb.forall(&struct_data, (), |gb, _, (fields, where_clauses), ()| {
-            let interner = gb.interner();
-
-            // struct is well-formed in terms of Sized
-            let sized_constraint_goal = compute_struct_sized_constraint(gb.db(), fields);
-
-            // (FromEnv(T: Eq) => ...)
-            gb.implies(
-                where_clauses
-                    .iter()
-                    .cloned()
-                    .map(|wc| wc.into_from_env_goal(interner)),
-                |gb| {
-                    // WellFormed(Vec<T>), for each field type `Vec<T>` or type that appears in the where clauses
-                    let mut input_types = Vec::new();
-                    // ...in a field type...
-                    fields.fold(gb.interner(), &mut input_types);
-                    // ...in a where clause.
-                    where_clauses.fold(gb.interner(), &mut input_types);
-
-                    gb.all(
-                        input_types
-                            .into_iter()
-                            .map(|ty| ty.well_formed().cast(interner))
-                            .chain(sized_constraint_goal.into_iter()),
-                    )
-                },
-            )
-        });
-
-        let wg_goal = wg_goal.into_closed_goal(interner);
+        let goal: Goal<I> = DomainGoal::WellFormed(WellFormed::Struct(struct_id)).cast(interner);
+        let wg_goal = goal.into_closed_goal(interner);
 
         let is_legal = match self.solver_choice.into_solver().solve(self.db, &wg_goal) {
             Some(sol) => sol.is_unique(),
@@ -248,15 +220,49 @@ where
             return Err(WfError::IllFormedTraitImpl(trait_id));
         }
 
-        let impl_goal = Goal::all(
-            interner,
-            impl_header_wf_goal(self.db, impl_id).into_iter().chain(
-                impl_datum
-                    .associated_ty_value_ids
-                    .iter()
-                    .filter_map(|&id| compute_assoc_ty_goal(self.db, id)),
-            ),
-        );
+        // Every associated const of the trait needs a value, unless the
+        // trait provides a default one, and the value must have the
+        // type the trait declares
+        if impl_datum.is_positive() {
+            let mut provided_consts = Vec::new();
+            for &id in &impl_datum.associated_const_value_ids {
+                let const_value = self.db.associated_const_value(id);
+                let const_goal = compute_assoc_const_goal(self.db, &impl_datum, &const_value);
+
+                debug!("WF associated const goal: {:?}", const_goal);
+
+                let is_well_typed = match self
+                    .solver_choice
+                    .into_solver()
+                    .solve(self.db, &const_goal.into_closed_goal(interner))
+                {
+                    Some(sol) => sol.is_unique(),
+                    None => false,
+                };
+
+                if !is_well_typed {
+                    let const_datum = self
+                        .db
+                        .associated_const_data(const_value.associated_const_id);
+                    return Err(WfError::IllTypedAssociatedConst(
+                        trait_id,
+                        const_datum.name.clone(),
+                    ));
+                }
+                provided_consts.push(const_value.associated_const_id);
+            }
+            for &const_id in &self.db.trait_datum(trait_id).associated_const_ids {
+                let const_datum = self.db.associated_const_data(const_id);
+                if !const_datum.has_default && !provided_consts.contains(&const_id) {
+                    return Err(WfError::MissingAssociatedConst(
+                        trait_id,
+                        const_datum.name.clone(),
+                    ));
+                }
+            }
+        }
+
+        let impl_goal: Goal<I> = DomainGoal::WellFormed(WellFormed::Impl(impl_id)).cast(interner);
 
         debug!("WF trait goal: {:?}", impl_goal);
 
@@ -275,14 +281,122 @@ where
             Err(WfError::IllFormedTraitImpl(trait_id))
         }
     }
+
+    /// Checks a single associated type value on its own: its type
+    /// must be well-formed and satisfy the bounds declared on the
+    /// associated type, assuming the impl's where clauses. This is
+    /// also checked as part of `verify_trait_impl`.
+    pub fn check_associated_ty_value(&self, id: AssociatedTyValueId<I>) -> Result<(), WfError<I>> {
+        let interner = self.db.interner();
+        let associated_ty_id = self.db.associated_ty_value(id).associated_ty_id;
+
+        let goal = match compute_assoc_ty_goal(self.db, id) {
+            Some(goal) => goal,
+            None => return Ok(()),
+        };
+
+        debug!("WF associated type value goal: {:?}", goal);
+
+        let is_legal = match self
+            .solver_choice
+            .into_solver()
+            .solve(self.db, &goal.into_closed_goal(interner))
+        {
+            Some(sol) => sol.is_unique(),
+            None => false,
+        };
+
+        if is_legal {
+            Ok(())
+        } else {
+            Err(WfError::IllFormedAssociatedTyValue(associated_ty_id))
+        }
+    }
+}
+
+/// Computes the goal that must hold for a struct declaration to be
+/// well-formed: under the struct's where clauses, every type appearing
+/// in its fields or where clauses is well-formed and the fields meet
+/// the `Sized` requirements. This is the condition of the
+/// `WellFormed(struct S)` rule.
+pub(crate) fn struct_wf_goal<I: Interner>(
+    db: &dyn RustIrDatabase<I>,
+    struct_id: StructId<I>,
+) -> Goal<I> {
+    // Given a struct like
+    //
+    // ```rust
+    // struct Foo<T> where T: Eq {
+    //     data: Vec<T>
+    // }
+    // ```
+    let struct_datum = db.struct_datum(struct_id);
+
+    let mut gb = GoalBuilder::new(db);
+    let struct_data = struct_datum
+        .binders
+        .map_ref(|b| (&b.fields, &b.where_clauses));
+
+    // We make a goal like...
+    //
+    // forall<T> { ... }
+    gb.forall(&struct_data, (), |gb, _, (fields, where_clauses), ()| {
+        let interner = gb.interner();
+
+        // struct is well-formed in terms of Sized
+        let sized_constraint_goal = compute_struct_sized_constraint(gb.db(), fields);
+
+        // (FromEnv(T: Eq) => ...)
+        gb.implies(
+            where_clauses
+                .iter()
+                .cloned()
+                .map(|wc| wc.into_from_env_goal(interner)),
+            |gb| {
+                // WellFormed(Vec<T>), for each field type `Vec<T>` or type that appears in the where clauses
+                let mut input_types = Vec::new();
+                // ...in a field type...
+                fields.fold(gb.interner(), &mut input_types);
+                // ...in a where clause.
+                where_clauses.fold(gb.interner(), &mut input_types);
+
+                gb.all(
+                    input_types
+                        .into_iter()
+                        .map(|ty| ty.well_formed().cast(interner))
+                        .chain(sized_constraint_goal.into_iter()),
+                )
+            },
+        )
+    })
+}
+
+/// Computes all the goals that must hold for an impl to be
+/// well-formed: the goal for the impl header (the trait ref and the
+/// input types of the where clauses) followed by one goal per
+/// associated type value. Each goal is quantified over the impl's
+/// binders. The input types of the impl header (including the self
+/// type) are not proven well-formed here; they are assumed by the
+/// environment each goal is proven in (see `impl_wf_environment`).
+pub(crate) fn impl_wf_goals<I: Interner>(
+    db: &dyn RustIrDatabase<I>,
+    impl_datum: &ImplDatum<I>,
+) -> Vec<Goal<I>> {
+    impl_header_wf_goal(db, impl_datum)
+        .into_iter()
+        .chain(
+            impl_datum
+                .associated_ty_value_ids
+                .iter()
+                .filter_map(|&id| compute_assoc_ty_goal(db, id)),
+        )
+        .collect()
 }
 
 fn impl_header_wf_goal<I: Interner>(
     db: &dyn RustIrDatabase<I>,
-    impl_id: ImplId<I>,
+    impl_datum: &ImplDatum<I>,
 ) -> Option<Goal<I>> {
-    let impl_datum = db.impl_datum(impl_id);
-
     if !impl_datum.is_positive() {
         return None;
     }
@@ -360,6 +474,56 @@ fn impl_wf_environment<'i, I: Interner>(
     wc.chain(types_wf)
 }
 
+/// The type an impl gives an associated const must be equal to the
+/// type the trait declares for it, with the trait's parameters taken
+/// from the impl's trait ref. Types are compared by proving equality
+/// rather than structurally, so that e.g. a projection in either type
+/// can be normalized first. For example, given
+///
+/// ```ignore
+/// trait Foo<T> { const C: T; }
+/// impl<U> Foo<Vec<U>> for u32 { const C: Vec<U> = _; }
+/// ```
+///
+/// we would issue the goal
+/// `forall<U> { if (/* impl WF environment */) { Vec<U> = Vec<U> } }`.
+fn compute_assoc_const_goal<I: Interner>(
+    db: &dyn RustIrDatabase<I>,
+    impl_datum: &ImplDatum<I>,
+    const_value: &AssociatedConstValue<I>,
+) -> Goal<I> {
+    let interner = db.interner();
+    let const_datum = db.associated_const_data(const_value.associated_const_id);
+
+    // Both types are under the impl's binders: the value's by
+    // construction, the declared one once the trait parameters are
+    // replaced by the impl's trait ref (which is itself under them).
+    let impl_fields = impl_datum.binders.map_ref(|bound| {
+        let declared_ty = const_datum
+            .ty
+            .substitute(interner, &bound.trait_ref.substitution);
+        (bound, (declared_ty, const_value.ty.value.clone()))
+    });
+
+    let mut gb = GoalBuilder::new(db);
+    // forall<P0...Pn> {...}
+    gb.forall(
+        &impl_fields,
+        (),
+        |gb, _, (impl_bound, (declared_ty, value_ty)), ()| {
+            let interner = gb.interner();
+
+            // if (WC && input types are well formed) { DeclaredTy = ValueTy }
+            let impl_wf =
+                impl_wf_environment(interner, &impl_bound.where_clauses, &impl_bound.trait_ref);
+            gb.implies(impl_wf, |_| EqGoal {
+                a: declared_ty.clone().cast(interner),
+                b: value_ty.clone().cast(interner),
+            })
+        },
+    )
+}
+
 /// Associated type values are special because they can be parametric (independently of
 /// the impl), so we issue a special goal which is quantified using the binders of the
 /// associated type value, for example in:
@@ -1,6 +1,16 @@
 use chalk_integration::db::ChalkDatabase;
+use chalk_integration::program::ItemId;
 use chalk_integration::query::LoweringDatabase;
-use chalk_solve::SolverChoice;
+use chalk_ir::cast::Cast;
+use chalk_ir::fold::shift::Shift;
+use chalk_ir::interner::ChalkIr;
+use chalk_ir::{
+    AliasTy, Binders, BoundVar, DebruijnIndex, Parameter, ParameterKind, TyData, TypeName,
+    WhereClause,
+};
+use chalk_rust_ir::{TraitFlags, UnstableFeature, WellKnownTrait};
+use chalk_solve::{RustIrDatabase, SolverChoice};
+use lalrpop_intern::intern;
 
 #[test]
 fn lower_success() {
@@ -66,6 +76,20 @@ fn auto_trait() {
     }
 }
 
+#[test]
+fn marker_trait() {
+    lowering_error! {
+        program {
+            #[marker] trait Foo {
+                type Item;
+            }
+        }
+        error_msg {
+            "marker trait `Foo` cannot define associated types"
+        }
+    }
+}
+
 #[test]
 fn negative_impl() {
     lowering_error! {
@@ -425,3 +449,1112 @@ fn fundamental_multiple_type_parameters() {
         }
     }
 }
+
+#[test]
+fn self_assoc_projection_in_trait_where_clause() {
+    let db = ChalkDatabase::with(
+        "
+            trait Clone { }
+            trait Foo<T> where <Self as Foo<T>>::Bar: Clone {
+                type Bar;
+            }
+            ",
+        SolverChoice::default(),
+    );
+    db.with_program(|program| {
+        let interner = &ChalkIr;
+        let trait_datum = program
+            .trait_data
+            .values()
+            .find(|datum| !datum.associated_ty_ids.is_empty())
+            .unwrap();
+        let projection =
+            trait_datum.self_assoc_projection(trait_datum.associated_ty_ids[0], interner);
+        assert_eq!(format!("{:?}", projection), "<^0.0 as Foo<^0.1>>::Bar");
+
+        // The where clause is under one more (empty) binder, so the
+        // projection appearing in it is shifted in by one.
+        let where_clause = &trait_datum.binders.value.where_clauses[0];
+        let self_ty = match &where_clause.value {
+            WhereClause::Implemented(trait_ref) => trait_ref.self_type_parameter(interner),
+            wc => panic!("unexpected where clause `{:?}`", wc),
+        };
+        match self_ty.data(interner) {
+            TyData::Alias(AliasTy::Projection(alias)) => {
+                assert_eq!(alias, &projection.shifted_in(interner))
+            }
+            ty => panic!("expected a projection, found `{:?}`", ty),
+        }
+    });
+}
+
+#[test]
+fn trait_datum_by_name() {
+    let db = ChalkDatabase::with(
+        "
+            #[lang(sized)]
+            trait Sized { }
+
+            trait Foo<T> where T: Sized { }
+            ",
+        SolverChoice::default(),
+    );
+    db.with_program(|program| {
+        let sized = db.trait_datum_by_name(&intern("Sized")).unwrap();
+        assert_eq!(sized.id, db.well_known_trait_id(WellKnownTrait::SizedTrait));
+
+        let foo = db.trait_datum_by_name(&intern("Foo")).unwrap();
+        assert_eq!(foo.id, program.trait_ids[&intern("Foo")]);
+        assert_eq!(foo.binders.binders.len(), 2);
+
+        assert!(db.trait_datum_by_name(&intern("Bar")).is_none());
+    });
+}
+
+#[test]
+fn unstable_features() {
+    let db = ChalkDatabase::with(
+        "
+            #[auto] trait Send { }
+            #[marker] trait Marker { }
+            trait Foo { }
+
+            #[fundamental]
+            struct Box<T> { }
+            struct Bar { }
+            ",
+        SolverChoice::default(),
+    );
+    db.with_program(|program| {
+        let trait_datum = |name: &str| db.trait_datum_by_name(&intern(name)).unwrap();
+        assert_eq!(
+            trait_datum("Send").unstable_features,
+            vec![UnstableFeature::AutoTraits]
+        );
+        assert_eq!(
+            trait_datum("Marker").unstable_features,
+            vec![UnstableFeature::MarkerTraitAttr]
+        );
+        assert!(!trait_datum("Foo").uses_unstable());
+
+        let struct_datum = |name: &str| &program.struct_data[&program.struct_ids[&intern(name)]];
+        assert_eq!(
+            struct_datum("Box").unstable_features,
+            vec![UnstableFeature::Fundamental]
+        );
+        assert!(!struct_datum("Bar").uses_unstable());
+    });
+}
+
+#[test]
+fn binders_from_fn() {
+    let interner = &ChalkIr;
+
+    let empty = Binders::from_fn(
+        vec![],
+        |parameters: &[Parameter<ChalkIr>]| parameters.len(),
+        interner,
+    );
+    assert!(empty.binders.is_empty());
+    assert_eq!(empty.value, 0);
+
+    let kinds = vec![
+        ParameterKind::Ty(()),
+        ParameterKind::Lifetime(()),
+        ParameterKind::Ty(()),
+    ];
+    let binders = Binders::from_fn(kinds.clone(), |parameters| parameters.to_vec(), interner);
+    assert_eq!(binders.binders, kinds);
+    let bound_var = |index| BoundVar::new(DebruijnIndex::INNERMOST, index);
+    assert_eq!(
+        binders.value,
+        vec![
+            bound_var(0).to_ty(interner).cast(interner),
+            bound_var(1).to_lifetime(interner).cast(interner),
+            bound_var(2).to_ty(interner).cast(interner),
+        ]
+    );
+}
+
+#[test]
+fn lang_item_structs() {
+    let db = ChalkDatabase::with(
+        "
+            #[lang(phantom_data)]
+            struct PhantomData<T> { }
+            ",
+        SolverChoice::default(),
+    );
+    db.with_program(|program| {
+        assert_eq!(
+            program.lang_items.phantom_data_struct(),
+            Some(program.struct_ids[&intern("PhantomData")])
+        );
+    });
+
+    let db = ChalkDatabase::with("struct PhantomData<T> { }", SolverChoice::default());
+    db.with_program(|program| {
+        assert_eq!(program.lang_items.phantom_data_struct(), None);
+    });
+}
+
+#[test]
+fn trait_flags_round_trip() {
+    assert_eq!(std::mem::size_of::<TraitFlags>(), 1);
+
+    for bits in 0..(1 << 6) {
+        let bit = |n: u32| bits & (1 << n) != 0;
+        let mut flags = TraitFlags::default();
+        flags.set_auto(bit(0));
+        flags.set_marker(bit(1));
+        flags.set_upstream(bit(2));
+        flags.set_fundamental(bit(3));
+        flags.set_non_enumerable(bit(4));
+        flags.set_coinductive(bit(5));
+
+        assert_eq!(flags.auto(), bit(0));
+        assert_eq!(flags.marker(), bit(1));
+        assert_eq!(flags.upstream(), bit(2));
+        assert_eq!(flags.fundamental(), bit(3));
+        assert_eq!(flags.non_enumerable(), bit(4));
+        assert_eq!(flags.coinductive(), bit(5));
+
+        // Clearing a flag leaves the others alone.
+        let mut cleared = flags;
+        cleared.set_auto(false);
+        assert!(!cleared.auto());
+        assert_eq!(cleared.coinductive(), flags.coinductive());
+        assert_eq!(cleared == flags, !bit(0));
+    }
+}
+
+#[test]
+fn associated_ty_value_all_binders() {
+    let db = ChalkDatabase::with(
+        "
+            struct Vec<T> { }
+            struct Iter<'a, T> { }
+
+            trait Iterable {
+                type Iter<'a>;
+            }
+
+            impl<T> Iterable for Vec<T> {
+                type Iter<'a> = Iter<'a, T>;
+            }
+            ",
+        SolverChoice::default(),
+    );
+    db.with_program(|program| {
+        let value = program.associated_ty_values.values().next().unwrap();
+        assert_eq!(
+            value.all_binders(),
+            &[ParameterKind::Lifetime(()), ParameterKind::Ty(())]
+        );
+    });
+}
+
+#[test]
+fn implicit_self_sized() {
+    let db = ChalkDatabase::with(
+        "
+            #[lang(sized)] trait Sized { }
+            trait Foo { }
+            trait Bar: ?Sized { }
+            ",
+        SolverChoice::default(),
+    );
+    db.with_program(|program| {
+        let interner = &ChalkIr;
+        let sized = program.trait_ids[&intern("Sized")];
+        let foo = &program.trait_data[&program.trait_ids[&intern("Foo")]];
+        let bar = &program.trait_data[&program.trait_ids[&intern("Bar")]];
+
+        assert!(foo.self_sized);
+        assert_eq!(foo.implicit_self_bounds(sized, interner).len(), 1);
+        assert!(!bar.self_sized);
+        assert!(bar.implicit_self_bounds(sized, interner).is_empty());
+    });
+
+    lowering_error! {
+        program {
+            trait Baz: ?Clone { }
+        }
+        error_msg {
+            "`?Clone` is not a valid bound, only `?Sized` is supported"
+        }
+    }
+}
+
+#[test]
+fn extract_minimal_program() {
+    use chalk_integration::query::check_program;
+    use std::sync::Arc;
+
+    let db = ChalkDatabase::with(
+        "
+            #[lang(sized)]
+            trait Sized { }
+
+            struct Foo { }
+            struct Bar<T> { next: Bar<T> }
+            struct Unrelated { }
+            trait Clone { }
+            trait Iterator where Self: Clone {
+                type Item;
+            }
+            trait Display { }
+
+            impl Clone for Foo { }
+            impl Display for Unrelated { }
+            impl Iterator for Foo {
+                type Item = Bar<Foo>;
+            }
+            ",
+        SolverChoice::default(),
+    );
+    db.with_program(|program| {
+        let iterator = program.trait_ids[&intern("Iterator")];
+        let (&impl_id, _) = program
+            .impl_data
+            .iter()
+            .find(|(_, impl_datum)| impl_datum.trait_id() == iterator)
+            .unwrap();
+
+        let minimal = program.extract_minimal_program(impl_id);
+        assert_eq!(minimal.struct_ids.len(), 2);
+        assert!(minimal.struct_ids.contains_key(&intern("Foo")));
+        assert!(minimal.struct_ids.contains_key(&intern("Bar")));
+        assert_eq!(minimal.trait_ids.len(), 3);
+        assert!(minimal.trait_ids.contains_key(&intern("Iterator")));
+        assert!(minimal.trait_ids.contains_key(&intern("Clone")));
+        assert!(minimal.trait_ids.contains_key(&intern("Sized")));
+        // The impl of the supertrait `Clone` is kept along with the
+        // extracted impl.
+        assert_eq!(minimal.impl_data.len(), 2);
+        assert_eq!(minimal.associated_ty_values.len(), 1);
+        assert_eq!(minimal.associated_ty_data.len(), 1);
+
+        check_program(&Arc::new(minimal), SolverChoice::default()).unwrap();
+    });
+}
+
+#[test]
+fn where_clauses_equivalent() {
+    let db = ChalkDatabase::with(
+        "
+            trait A { }
+            trait B { }
+            trait Foo { }
+            trait Bar { }
+            trait Baz { }
+            struct Vec<T> { }
+
+            impl<T> Foo for Vec<T> where T: A, T: B { }
+            impl<T> Bar for Vec<T> where T: B, T: A, T: B { }
+            impl<T> Baz for Vec<T> where T: A { }
+            ",
+        SolverChoice::default(),
+    );
+    db.with_program(|program| {
+        let where_clauses = |trait_name: &str| {
+            let trait_id = program.trait_ids[&intern(trait_name)];
+            program
+                .impl_data
+                .values()
+                .find(|impl_datum| impl_datum.trait_id() == trait_id)
+                .unwrap()
+                .binders
+                .value
+                .where_clauses
+                .clone()
+        };
+
+        let foo = where_clauses("Foo");
+        let bar = where_clauses("Bar");
+        let baz = where_clauses("Baz");
+        assert_ne!(foo, bar);
+        assert!(chalk_rust_ir::where_clauses_equivalent(&foo, &bar));
+        assert!(!chalk_rust_ir::where_clauses_equivalent(&foo, &baz));
+        assert!(!chalk_rust_ir::where_clauses_equivalent(&baz, &foo));
+    });
+}
+
+#[test]
+fn program_validate() {
+    let db = ChalkDatabase::with(
+        "
+            struct Foo { }
+            trait Iterator { type Item; }
+            impl Iterator for Foo {
+                type Item = Foo;
+            }
+            ",
+        SolverChoice::default(),
+    );
+    db.with_program(|program| {
+        assert_eq!(program.validate(), vec![]);
+
+        let trait_id = program.trait_ids[&intern("Iterator")];
+        let mut broken = program.clone();
+        broken.trait_data.remove(&trait_id);
+        let references: Vec<_> = broken
+            .validate()
+            .into_iter()
+            .map(|error| error.reference)
+            .collect();
+        assert_eq!(
+            references,
+            vec![
+                ItemId::Trait(trait_id),
+                ItemId::Trait(trait_id),
+                ItemId::Trait(trait_id)
+            ]
+        );
+    });
+}
+
+#[test]
+fn impl_contains_type() {
+    let db = ChalkDatabase::with(
+        "
+            trait Foo { }
+            struct Vec<T> { }
+            struct Bar { }
+            struct Baz { }
+            struct Unused { }
+            impl<T> Foo for Vec<T> where T: Foo, Vec<Bar>: Foo { }
+            ",
+        SolverChoice::default(),
+    );
+    db.with_program(|program| {
+        let impl_datum = program.impl_data.values().next().unwrap();
+        let contains = |name: &str| {
+            let name = TypeName::Struct(program.struct_ids[&intern(name)]);
+            impl_datum.contains_type(name, &ChalkIr)
+        };
+        assert!(contains("Vec"));
+        assert!(contains("Bar"));
+        assert!(!contains("Baz"));
+        assert!(!contains("Unused"));
+    });
+}
+
+#[test]
+fn impl_unconstrained_params() {
+    let db = ChalkDatabase::with(
+        "
+            struct Vec<T> { }
+            trait Iterator { type Item; }
+            trait Unconstrained { }
+            trait WhereClauseOnly { }
+            trait Projection { }
+            trait Chained { }
+            impl<T> Unconstrained for Vec<Vec<Vec<Vec<T>>>> { }
+            impl<T, U> Unconstrained for Vec<T> { }
+            impl<T, U> WhereClauseOnly for Vec<T> where U: Iterator { }
+            impl<I, T> Projection for Vec<I> where I: Iterator<Item = T> { }
+            impl<I, T, U> Chained for Vec<I> where T: Iterator<Item = U>, I: Iterator<Item = T> { }
+            ",
+        SolverChoice::default(),
+    );
+    db.with_program(|program| {
+        let unconstrained = |trait_name: &str| {
+            let trait_id = program.trait_ids[&intern(trait_name)];
+            let mut params: Vec<_> = program
+                .impl_data
+                .values()
+                .filter(|impl_datum| impl_datum.trait_id() == trait_id)
+                .map(|impl_datum| impl_datum.unconstrained_params(&ChalkIr))
+                .collect();
+            params.sort();
+            params
+        };
+        assert_eq!(unconstrained("Unconstrained"), vec![vec![], vec![1]]);
+        assert_eq!(unconstrained("WhereClauseOnly"), vec![vec![1]]);
+        assert_eq!(unconstrained("Projection"), vec![vec![]]);
+        assert_eq!(unconstrained("Chained"), vec![vec![]]);
+    });
+}
+
+#[test]
+fn drop_trait() {
+    let db = ChalkDatabase::with(
+        "
+            #[lang(drop)] trait Drop { }
+            trait Clone { }
+            struct Foo<T> where T: Clone { }
+            impl<T> Drop for Foo<T> where T: Clone { }
+            ",
+        SolverChoice::default(),
+    );
+    db.with_program(|program| {
+        let drop = program.trait_ids[&intern("Drop")];
+        assert_eq!(
+            program.trait_data[&drop].well_known,
+            Some(WellKnownTrait::DropTrait)
+        );
+        assert_eq!(program.well_known_trait_id(WellKnownTrait::DropTrait), drop);
+        assert!(program
+            .impl_data
+            .values()
+            .any(|impl_datum| impl_datum.trait_id() == drop));
+    });
+}
+
+#[test]
+fn fn_def_datum() {
+    use chalk_ir::interner::RawId;
+    use chalk_ir::{PlaceholderIndex, UniverseIndex};
+    use chalk_rust_ir::{FnDefDatum, FnDefDatumBound, FnDefId};
+
+    let interner = &ChalkIr;
+    // fn identity<T>(x: T) -> T
+    let t = BoundVar::new(DebruijnIndex::INNERMOST, 0).to_ty(interner);
+    let datum: FnDefDatum<ChalkIr> = FnDefDatum {
+        id: FnDefId(RawId { index: 0 }),
+        binders: Binders {
+            binders: vec![ParameterKind::Ty(())],
+            value: FnDefDatumBound {
+                argument_types: vec![t.clone()],
+                return_type: t.clone(),
+                where_clauses: vec![],
+            },
+        },
+    };
+
+    let sig = datum.sig();
+    assert_eq!(sig.binders, datum.binders.binders);
+    assert_eq!(sig.value.0, &[t.clone()][..]);
+    assert_eq!(sig.value.1, &t);
+
+    let placeholder = PlaceholderIndex {
+        ui: UniverseIndex::ROOT,
+        idx: 0,
+    }
+    .to_ty(interner);
+    let parameter: Parameter<ChalkIr> = placeholder.clone().cast(interner);
+    let bound = datum.binders.substitute(interner, &[parameter]);
+    assert_eq!(bound.argument_types, vec![placeholder.clone()]);
+    assert_eq!(bound.return_type, placeholder);
+}
+
+#[test]
+fn closure_datum_kind() {
+    use chalk_ir::interner::RawId;
+    use chalk_rust_ir::{ClosureDatum, ClosureDatumBound, ClosureId, ClosureKind};
+
+    let interner = &ChalkIr;
+    let t = BoundVar::new(DebruijnIndex::INNERMOST, 0).to_ty(interner);
+    let closure = |index, kind| -> ClosureDatum<ChalkIr> {
+        ClosureDatum {
+            id: ClosureId(RawId { index }),
+            kind,
+            binders: Binders {
+                binders: vec![ParameterKind::Ty(())],
+                value: ClosureDatumBound {
+                    upvars: vec![t.clone()],
+                    argument_types: vec![],
+                    return_type: t.clone(),
+                },
+            },
+        }
+    };
+
+    // `|| y.len()` only reads its upvar, `|| y.push(0)` mutates it.
+    let reads = closure(0, ClosureKind::Fn);
+    let mutates = closure(1, ClosureKind::FnMut);
+    let consumes = closure(2, ClosureKind::FnOnce);
+    assert_eq!(reads.kind(), ClosureKind::Fn);
+    assert_eq!(mutates.kind(), ClosureKind::FnMut);
+    assert_eq!(consumes.kind(), ClosureKind::FnOnce);
+
+    let implemented = |datum: &ClosureDatum<ChalkIr>| {
+        vec![ClosureKind::Fn, ClosureKind::FnMut, ClosureKind::FnOnce]
+            .into_iter()
+            .filter(|&trait_kind| datum.kind().implements(trait_kind))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        implemented(&reads),
+        vec![ClosureKind::Fn, ClosureKind::FnMut, ClosureKind::FnOnce]
+    );
+    assert_eq!(
+        implemented(&mutates),
+        vec![ClosureKind::FnMut, ClosureKind::FnOnce]
+    );
+    assert_eq!(implemented(&consumes), vec![ClosureKind::FnOnce]);
+}
+
+#[test]
+fn opaque_ty_bounds_on_self() {
+    use chalk_ir::interner::RawId;
+    use chalk_ir::{OpaqueTyId, TraitId};
+    use chalk_rust_ir::{InlineBound, OpaqueTyDatum, OpaqueTyDatumBound, TraitBound};
+
+    let interner = &ChalkIr;
+    let iterator = TraitId(RawId { index: 0 });
+    // fn foo<'a, T>(..) -> impl Iterator + 'a, with hidden type `T`
+    let t = BoundVar::new(DebruijnIndex::INNERMOST, 1).to_ty(interner);
+    let datum: OpaqueTyDatum<ChalkIr> = OpaqueTyDatum {
+        opaque_ty_id: OpaqueTyId(RawId { index: 1 }),
+        bound: Binders {
+            binders: vec![ParameterKind::Lifetime(()), ParameterKind::Ty(())],
+            value: OpaqueTyDatumBound {
+                hidden_ty: t.clone(),
+                bounds: vec![Binders {
+                    binders: vec![],
+                    value: InlineBound::TraitBound(TraitBound {
+                        trait_id: iterator,
+                        args_no_self: vec![],
+                    }),
+                }],
+            },
+        },
+    };
+
+    let bounds = datum.bounds_on_self(interner);
+    assert_eq!(bounds.len(), 1);
+    match &bounds[0].value {
+        WhereClause::Implemented(trait_ref) => {
+            assert_eq!(trait_ref.trait_id, iterator);
+            assert_eq!(
+                trait_ref.self_type_parameter(interner),
+                t.shifted_in(interner)
+            );
+        }
+        where_clause => panic!("unexpected where clause: {:?}", where_clause),
+    }
+}
+
+#[test]
+fn adt_datum() {
+    use chalk_rust_ir::{AdtDatum, AdtDatumBound, AdtKind, AdtVariantDatum};
+
+    let db = ChalkDatabase::with(
+        "
+            #[lang(sized)] trait Sized { }
+            struct Foo { }
+            struct Bar<T> { foo: Foo, t: T }
+            struct Either<T> { }
+            ",
+        SolverChoice::default(),
+    );
+    db.with_program(|program| {
+        let interner = &ChalkIr;
+        let bar_id = program.struct_ids[&intern("Bar")];
+        let either_id = program.struct_ids[&intern("Either")];
+        let bar = &program.struct_data[&bar_id];
+
+        // A struct is an ADT with a single variant.
+        let bar_adt = AdtDatum::from((**bar).clone());
+        assert_eq!(bar_adt.kind, AdtKind::Struct);
+        assert_eq!(bar_adt.name(interner), bar.name(interner));
+        assert_eq!(bar_adt.fields(), Some(&bar.binders.value.fields[..]));
+
+        // enum Either<T> { Left(Foo), Right(T, T) }
+        let foo = bar.binders.value.fields[0].clone();
+        let t = BoundVar::new(DebruijnIndex::INNERMOST, 0).to_ty(interner);
+        let either = AdtDatum {
+            binders: Binders {
+                binders: vec![ParameterKind::Ty(())],
+                value: AdtDatumBound {
+                    variants: vec![
+                        AdtVariantDatum {
+                            fields: vec![foo.clone()],
+                        },
+                        AdtVariantDatum {
+                            fields: vec![t.clone(), t.clone()],
+                        },
+                    ],
+                    where_clauses: vec![],
+                },
+            },
+            id: either_id,
+            kind: AdtKind::Enum,
+            flags: bar.flags,
+            unstable_features: vec![],
+        };
+        assert_eq!(either.fields(), None);
+        assert_eq!(either.variants().len(), 2);
+        assert_eq!(either.variants()[0].fields, vec![foo]);
+        assert_eq!(either.variants()[1].fields, vec![t.clone(), t]);
+
+        // A struct without a variant has no fields to report.
+        let mut empty = bar_adt;
+        empty.binders.value.variants.clear();
+        assert_eq!(empty.fields(), None);
+    });
+}
+
+#[test]
+fn parse_error_location() {
+    let error = chalk_parse::parse_program("struct Foo { }\ntrait Bar { fn }").unwrap_err();
+    assert_eq!(error.line, 2);
+    assert_eq!(error.column, 13);
+    assert_eq!(error.got, "fn");
+    assert!(error.expected.contains(&r#""type""#.to_string()));
+
+    let error = chalk_parse::parse_goal("exists<T> {").unwrap_err();
+    assert_eq!((error.line, error.column), (1, 12));
+    assert_eq!(error.got, "EOF");
+}
+
+#[test]
+fn associated_const_data() {
+    let db = ChalkDatabase::with(
+        "
+            struct Usize { }
+            struct Foo { }
+            trait Bounded {
+                const MAX: Usize;
+            }
+            impl Bounded for Foo {
+                const MAX: Usize = _;
+            }
+            ",
+        SolverChoice::default(),
+    );
+    db.with_program(|program| {
+        let trait_id = program.trait_ids[&intern("Bounded")];
+        let trait_datum = &program.trait_data[&trait_id];
+        assert_eq!(trait_datum.associated_const_ids.len(), 1);
+
+        let const_datum = &program.associated_const_data[&trait_datum.associated_const_ids[0]];
+        assert_eq!(const_datum.trait_id, trait_id);
+        assert_eq!(const_datum.name, intern("MAX"));
+        assert!(!const_datum.has_default);
+
+        let (_, impl_datum) = program.impl_data.iter().next().unwrap();
+        assert_eq!(impl_datum.associated_const_value_ids.len(), 1);
+        let value = &program.associated_const_values[&impl_datum.associated_const_value_ids[0]];
+        assert_eq!(value.associated_const_id, const_datum.id);
+        assert_eq!(value.ty.value, const_datum.ty.value);
+    });
+}
+
+#[test]
+fn impl_const_not_in_trait() {
+    lowering_error! {
+        program {
+            struct Usize { }
+            trait Foo { }
+            impl Foo for Usize {
+                const N: Usize = _;
+            }
+        }
+        error_msg {
+            "no associated const `N` defined in trait"
+        }
+    }
+}
+
+/// Asserts that folding `value` with a `NullFolder` gives it back
+/// unchanged.
+fn check_null_fold<T>(value: &T)
+where
+    T: chalk_ir::fold::Fold<ChalkIr, Result = T> + PartialEq + std::fmt::Debug,
+{
+    let folded = value
+        .fold_with(
+            &mut chalk_ir::fold::NullFolder::new(&ChalkIr),
+            DebruijnIndex::INNERMOST,
+        )
+        .unwrap();
+    assert_eq!(&folded, value);
+}
+
+#[test]
+fn null_folder_is_identity() {
+    use chalk_ir::fold::Fold;
+    use chalk_solve::ext::GoalExt;
+    use std::fmt::Debug;
+
+    fn check_all<'a, T: Fold<ChalkIr, Result = T> + PartialEq + Debug + 'a>(
+        values: impl IntoIterator<Item = &'a T>,
+    ) {
+        values.into_iter().for_each(check_null_fold);
+    }
+
+    let db = ChalkDatabase::with(
+        "
+            struct Foo<'a, T> where T: Bar { t: T }
+            trait Bar { type Item<'a>: Bar; }
+            trait Baz<T> where Self: Bar { }
+            impl<'b, U> Bar for Foo<'b, U> where U: Bar { type Item<'a> = U; }
+            impl<T> Baz<dyn Bar> for T where T: Bar { }
+            ",
+        SolverChoice::default(),
+    );
+    db.with_program(|program| {
+        check_all(program.struct_data.values().map(|datum| &datum.binders));
+        check_all(program.trait_data.values().map(|datum| &datum.binders));
+        check_all(program.impl_data.values().map(|datum| &datum.binders));
+        check_all(program.associated_ty_data.values().map(|datum| &datum.binders));
+        check_all(program.associated_ty_values.values().map(|value| &**value));
+        check_all(&db.environment().unwrap().program_clauses);
+
+        let goal = db
+            .parse_and_lower_goal(
+                "forall<T> { exists<'a, U> { if (T: Bar) { Foo<'a, U>: Baz<<T as Bar>::Item<'a>> } } }",
+            )
+            .unwrap();
+        check_null_fold(&goal);
+        check_null_fold(&goal.into_peeled_goal(db.interner()).canonical);
+    });
+}
+
+#[test]
+fn null_folder_is_identity_for_each_type() {
+    use chalk_ir::fold::Fold;
+    use chalk_ir::*;
+    use std::fmt::Debug;
+
+    fn check<T: Fold<ChalkIr, Result = T> + PartialEq + Debug>(value: T) -> T {
+        check_null_fold(&value);
+        value
+    }
+
+    let db = ChalkDatabase::with(
+        "
+            struct Foo<'a, T> { }
+            trait Bar { type Item; }
+            ",
+        SolverChoice::default(),
+    );
+    db.with_program(|program| {
+        let interner = &ChalkIr;
+        let foo_id = program.struct_ids[&intern("Foo")];
+        let bar_id = program.trait_ids[&intern("Bar")];
+        let item_id = *program.associated_ty_data.keys().next().unwrap();
+        let placeholder = |idx| PlaceholderIndex {
+            ui: UniverseIndex::ROOT,
+            idx,
+        };
+
+        // Every kind of lifetime and type variable.
+        let bound_lifetime =
+            check(BoundVar::new(DebruijnIndex::INNERMOST, 0).to_lifetime(interner));
+        let placeholder_lifetime = check(placeholder(0).to_lifetime(interner));
+        check(InferenceVar::from(0).to_lifetime(interner));
+        let bound_ty = check(BoundVar::new(DebruijnIndex::INNERMOST, 1).to_ty(interner));
+        let placeholder_ty = check(placeholder(1).to_ty(interner));
+        let inference_ty = check(InferenceVar::from(1).to_ty(interner));
+
+        let substitution = check(Substitution::from(
+            interner,
+            [
+                check(bound_lifetime.cast::<Parameter<ChalkIr>>(interner)),
+                bound_ty.clone().cast(interner),
+            ],
+        ));
+        let application = check(ApplicationTy {
+            name: check(TypeName::Struct(foo_id)),
+            substitution,
+        });
+        let foo_ty = check(TyData::Apply(application).intern(interner));
+        let trait_ref = check(TraitRef {
+            trait_id: bar_id,
+            substitution: Substitution::from1(interner, foo_ty.clone()),
+        });
+        let alias = check(AliasTy::Projection(ProjectionTy {
+            associated_ty_id: item_id,
+            substitution: Substitution::from1(interner, placeholder_ty.clone()),
+        }));
+        check(TyData::Alias(alias.clone()).intern(interner));
+        let alias_eq = check(AliasEq {
+            alias: alias.clone(),
+            ty: inference_ty.clone(),
+        });
+
+        let where_clauses = [
+            check(WhereClause::Implemented(trait_ref.clone())),
+            WhereClause::AliasEq(alias_eq),
+        ];
+        let quantified_where_clauses = check(QuantifiedWhereClauses::from(
+            interner,
+            where_clauses.iter().map(|where_clause| {
+                check(Binders {
+                    binders: vec![ParameterKind::Lifetime(())],
+                    value: where_clause.clone(),
+                })
+            }),
+        ));
+        check(
+            TyData::Dyn(check(DynTy {
+                bounds: Binders {
+                    binders: vec![ParameterKind::Ty(())],
+                    value: quantified_where_clauses,
+                },
+            }))
+            .intern(interner),
+        );
+
+        let domain_goals = [
+            check(DomainGoal::Holds(where_clauses[1].clone())),
+            DomainGoal::WellFormed(check(WellFormed::Trait(trait_ref.clone()))),
+            DomainGoal::FromEnv(check(FromEnv::Ty(foo_ty.clone()))),
+            DomainGoal::Normalize(check(Normalize {
+                alias,
+                ty: bound_ty.clone(),
+            })),
+        ];
+        let implication = check(ProgramClauseImplication {
+            consequence: domain_goals[0].clone(),
+            conditions: check(Goals::from(interner, domain_goals[1..].iter().cloned())),
+        });
+        let clauses = [
+            check(ProgramClauseData::Implies(implication.clone()).intern(interner)),
+            ProgramClauseData::ForAll(Binders {
+                binders: vec![ParameterKind::Ty(())],
+                value: implication,
+            })
+            .intern(interner),
+        ];
+        check(ProgramClauses::from(interner, clauses.clone()));
+        let environment = check(Environment::new(interner).add_clauses(interner, clauses.clone()));
+
+        let eq_goal = check(EqGoal {
+            a: foo_ty.clone().cast(interner),
+            b: placeholder_ty.clone().cast(interner),
+        });
+        let goal = check(
+            GoalData::Quantified(
+                QuantifierKind::ForAll,
+                Binders {
+                    binders: vec![ParameterKind::Ty(())],
+                    value: GoalData::Implies(
+                        ProgramClauses::from(interner, clauses),
+                        GoalData::All(Goals::from(
+                            interner,
+                            [
+                                GoalData::EqGoal(eq_goal).intern(interner),
+                                GoalData::Not(domain_goals[2].clone().cast(interner))
+                                    .intern(interner),
+                                GoalData::CannotProve(()).intern(interner),
+                            ],
+                        ))
+                        .intern(interner),
+                    )
+                    .intern(interner),
+                },
+            )
+            .intern(interner),
+        );
+        check(Canonical {
+            binders: vec![ParameterKind::Ty(UniverseIndex::ROOT)],
+            value: check(InEnvironment::new(&environment, goal.clone())),
+        });
+
+        let constraints = vec![InEnvironment::new(
+            &environment,
+            check(Constraint::LifetimeEq(bound_lifetime, placeholder_lifetime)),
+        )];
+        let subst = Substitution::from(interner, [foo_ty, placeholder_ty, inference_ty]);
+        check(ConstrainedSubst {
+            subst: subst.clone(),
+            constraints: constraints.clone(),
+        });
+        check(AnswerSubst {
+            subst,
+            constraints,
+            delayed_subgoals: vec![InEnvironment::new(&environment, goal)],
+        });
+    });
+}
+
+#[test]
+fn impl_datum_builder() {
+    use chalk_ir::{ApplicationTy, Substitution, TraitRef};
+    use chalk_rust_ir::ImplDatumBuilder;
+
+    let db = ChalkDatabase::with(
+        "
+            struct Vec<T> { }
+            trait Clone { }
+            trait Foo { }
+            impl<T> Foo for Vec<T> where T: Clone { }
+            ",
+        SolverChoice::default(),
+    );
+    db.with_program(|program| {
+        let interner = &ChalkIr;
+        let vec_id = program.struct_ids[&intern("Vec")];
+        let clone_id = program.trait_ids[&intern("Clone")];
+        let foo_id = program.trait_ids[&intern("Foo")];
+
+        let mut builder = ImplDatumBuilder::new(interner);
+        let t = builder.with_ty_param();
+        let vec_t = TyData::Apply(ApplicationTy {
+            name: TypeName::Struct(vec_id),
+            substitution: Substitution::from1(interner, t.clone()),
+        })
+        .intern(interner);
+        builder
+            .set_trait_ref(TraitRef {
+                trait_id: foo_id,
+                substitution: Substitution::from1(interner, vec_t),
+            })
+            .add_where_clause(WhereClause::Implemented(TraitRef {
+                trait_id: clone_id,
+                substitution: Substitution::from1(interner, t),
+            }));
+        let built = builder.build();
+
+        // The where clause sits under its own (empty) binder, so `T`
+        // is shifted in by one there.
+        let where_clause = &built.binders.value.where_clauses[0].value;
+        let shifted_t = BoundVar::new(DebruijnIndex::ONE, 0).to_ty(interner);
+        assert_eq!(
+            where_clause,
+            &WhereClause::Implemented(TraitRef {
+                trait_id: clone_id,
+                substitution: Substitution::from1(interner, shifted_t),
+            })
+        );
+
+        let (_, lowered) = program
+            .impl_data
+            .iter()
+            .find(|(_, impl_datum)| impl_datum.trait_id() == foo_id)
+            .unwrap();
+        assert_eq!(&built, &**lowered);
+    });
+}
+
+#[test]
+fn display_inline_bounds() {
+    use chalk_rust_ir::DisplayBound;
+
+    let db = ChalkDatabase::with(
+        "
+            struct u32 { }
+            trait Clone { }
+            trait Foo<T> { }
+            trait Baz<'a> { }
+            trait Iterator { type Item; }
+            trait Convert<T> { type Output; }
+            trait Bounds {
+                type A: Clone;
+                type B: Foo<u32>;
+                type C: Iterator<Item = u32>;
+                type D: Convert<u32, Output = u32>;
+                type E: forall<'a> Baz<'a>;
+            }
+            ",
+        SolverChoice::default(),
+    );
+    db.with_program(|program| {
+        let display = |name: &str| {
+            let (_, datum) = program
+                .associated_ty_data
+                .iter()
+                .find(|(_, datum)| datum.name == intern(name))
+                .unwrap();
+            datum.binders.value.bounds[0].display_bound()
+        };
+        assert_eq!(display("A"), ": Clone");
+        assert_eq!(display("B"), ": Foo<u32>");
+        assert_eq!(display("C"), ": Iterator<Item = u32>");
+        assert_eq!(display("D"), ": Convert<u32, Output = u32>");
+        assert_eq!(display("E"), ": forall<lifetime> Baz<'^0.0>");
+    });
+}
+
+#[test]
+fn describe_inline_bounds() {
+    use chalk_ir::{ApplicationTy, Substitution, TyData, TypeName};
+    use chalk_rust_ir::DescribeBound;
+
+    let db = ChalkDatabase::with(
+        "
+            struct u32 { }
+            trait Clone { }
+            trait Baz<'a> { }
+            trait Iterator { type Item; }
+            trait Bounds {
+                type A: Clone;
+                type C: Iterator<Item = u32>;
+                type E: forall<'a> Baz<'a>;
+            }
+            ",
+        SolverChoice::default(),
+    );
+    db.with_program(|program| {
+        let interner = &ChalkIr;
+        let u32_ty = TyData::Apply(ApplicationTy {
+            name: TypeName::Struct(program.struct_ids[&intern("u32")]),
+            substitution: Substitution::empty(interner),
+        })
+        .intern(interner);
+        let describe = |name: &str| {
+            let (_, datum) = program
+                .associated_ty_data
+                .iter()
+                .find(|(_, datum)| datum.name == intern(name))
+                .unwrap();
+            datum.binders.value.bounds[0].describe(interner, u32_ty.clone())
+        };
+        assert_eq!(describe("A"), "u32: Clone");
+        assert_eq!(describe("C"), "<u32 as Iterator>::Item = u32");
+        assert_eq!(describe("E"), "forall<lifetime> u32: Baz<'^0.0>");
+    });
+}
+
+#[test]
+fn gat_higher_ranked_bounds_on_self() {
+    use chalk_ir::{ProjectionTy, Substitution, TraitRef};
+
+    let db = ChalkDatabase::with(
+        "
+            trait Baz<'x, 'y> { }
+            trait Foo {
+                type Item<'a>: forall<'x> Baz<'x, 'a>;
+            }
+            ",
+        SolverChoice::default(),
+    );
+    db.with_program(|program| {
+        let interner = &ChalkIr;
+        let baz_id = program.trait_ids[&intern("Baz")];
+        let (&item_id, item) = program.associated_ty_data.iter().next().unwrap();
+
+        // The associated type binds `['a, Self]`; the `forall<'x>`
+        // adds one more binder, so inside the where clause `'x` is
+        // `^0.0` while `'a` and `Self` are shifted to `^1.0` and `^1.1`.
+        let lifetime = |debruijn, index| -> Parameter<ChalkIr> {
+            BoundVar::new(debruijn, index)
+                .to_lifetime(interner)
+                .cast(interner)
+        };
+        let outer = DebruijnIndex::ONE;
+        let self_ty: Parameter<ChalkIr> = ProjectionTy {
+            associated_ty_id: item_id,
+            substitution: Substitution::from(
+                interner,
+                vec![
+                    lifetime(outer, 0),
+                    BoundVar::new(outer, 1).to_ty(interner).cast(interner),
+                ],
+            ),
+        }
+        .intern(interner)
+        .cast(interner);
+        let expected = Binders {
+            binders: vec![ParameterKind::Lifetime(())],
+            value: WhereClause::Implemented(TraitRef {
+                trait_id: baz_id,
+                substitution: Substitution::from(
+                    interner,
+                    vec![
+                        self_ty,
+                        lifetime(DebruijnIndex::INNERMOST, 0),
+                        lifetime(outer, 0),
+                    ],
+                ),
+            }),
+        };
+
+        assert_eq!(item.bounds_on_self(interner), vec![expected]);
+    });
+}
@@ -143,6 +143,50 @@ fn auto_trait_with_impls() {
     }
 }
 
+/// A negative impl only overrides the auto trait rule for the struct
+/// it names; other structs still get the structural rule, and only
+/// those that contain the struct inherit the negative answer.
+#[test]
+fn negative_auto_trait_impl_only_affects_its_struct() {
+    test! {
+        program {
+            #[auto] trait Send { }
+
+            struct i32 { }
+            struct f32 { }
+            struct Rc<T> { }
+            struct Local { value: f32 }
+            struct Shared { value: Rc<f32> }
+
+            impl<T> !Send for Rc<T> { }
+        }
+
+        goal {
+            forall<T> { Rc<T>: Send }
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            Local: Send
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            Shared: Send
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            i32: Send
+        } yields {
+            "Unique"
+        }
+    }
+}
+
 /// This Flounders because auto traits can't be enumerated
 #[test]
 fn auto_traits_flounder() {
@@ -162,3 +206,47 @@ fn auto_traits_flounder() {
         }
     }
 }
+
+#[test]
+fn phantom_data() {
+    test! {
+        program {
+            #[auto] trait Send { }
+
+            #[lang(phantom_data)]
+            struct PhantomData<T> { }
+
+            struct i32 { }
+            struct f32 { }
+            struct Vec<T> { }
+            struct Foo<T> { data: PhantomData<Vec<T>> }
+
+            impl<T> Send for Vec<T> where T: Send { }
+            impl !Send for i32 { }
+        }
+
+        goal {
+            PhantomData<i32>: Send
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            PhantomData<f32>: Send
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            Foo<i32>: Send
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            Foo<f32>: Send
+        } yields {
+            "Unique"
+        }
+    }
+}
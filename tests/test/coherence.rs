@@ -1,4 +1,11 @@
+use chalk_integration::db::ChalkDatabase;
 use chalk_integration::query::LoweringDatabase;
+use chalk_ir::interner::ChalkIr;
+use chalk_ir::{ApplicationTy, Substitution, TraitRef, Ty, TypeName};
+use chalk_solve::coherence::CoherenceSolver;
+use chalk_solve::ext::TypeNameExt;
+use chalk_solve::SolverChoice;
+use lalrpop_intern::intern;
 
 #[test]
 fn two_impls_for_same_type() {
@@ -287,6 +294,34 @@ fn fundamental_traits() {
     }
 }
 
+#[test]
+fn fundamental_trait_orphan_check() {
+    // Being fundamental only affects negative reasoning about a trait; the orphan rules are the
+    // same as for any other upstream trait. A local type can implement it...
+    lowering_success! {
+        program {
+            #[upstream] #[fundamental] trait Foo { }
+            struct Local { }
+            struct Wrapper<T> { }
+
+            impl Foo for Local { }
+            impl<T> Foo for Wrapper<T> { }
+        }
+    }
+
+    // ...but an upstream type cannot.
+    lowering_error! {
+        program {
+            #[upstream] #[fundamental] trait Foo { }
+            #[upstream] struct str { }
+
+            impl Foo for str { }
+        } error_msg {
+            "impl for trait `Foo` violates the orphan rules"
+        }
+    }
+}
+
 #[test]
 fn orphan_check() {
     // These tests are largely adapted from the compile-fail coherence-*.rs tests from rustc
@@ -459,3 +494,126 @@ fn orphan_check() {
         }
     }
 }
+
+#[test]
+fn most_specialized_impl() {
+    let db = ChalkDatabase::with(
+        "
+            trait Foo { }
+            trait Bar { }
+            struct Vec<T> { }
+            struct Local { }
+            struct Other { }
+
+            impl<T> Foo for Vec<T> { }
+            impl Foo for Vec<Local> { }
+
+            impl<T> Bar for T { }
+            impl Bar for Local { }
+
+            #[marker] trait Marker { }
+            impl Marker for Local { }
+            impl Marker for Local { }
+            ",
+        SolverChoice::default(),
+    );
+    db.with_program(|program| {
+        let interner = &ChalkIr;
+        let ty = |name: &str, args: Vec<Ty<ChalkIr>>| {
+            ApplicationTy {
+                name: TypeName::Struct(program.struct_ids[&intern(name)]),
+                substitution: Substitution::from(interner, args),
+            }
+            .intern(interner)
+        };
+        let trait_ref = |name: &str, self_ty: Ty<ChalkIr>| TraitRef {
+            trait_id: program.trait_ids[&intern(name)],
+            substitution: Substitution::from1(interner, self_ty),
+        };
+        // The impls of `trait_name` that have `binders` parameters.
+        let impl_of = |trait_name: &str, binders: usize| {
+            let trait_id = program.trait_ids[&intern(trait_name)];
+            program
+                .impl_data
+                .iter()
+                .filter(|(_, datum)| datum.trait_id() == trait_id)
+                .filter(|(_, datum)| datum.binders.len() == binders)
+                .map(|(&id, _)| id)
+                .collect::<Vec<_>>()
+        };
+
+        let foo = CoherenceSolver::new(
+            program,
+            SolverChoice::default(),
+            program.trait_ids[&intern("Foo")],
+        );
+        let vec_local = trait_ref("Foo", ty("Vec", vec![ty("Local", vec![])]));
+        let vec_other = trait_ref("Foo", ty("Vec", vec![ty("Other", vec![])]));
+        let other = trait_ref("Foo", ty("Other", vec![]));
+        assert_eq!(
+            foo.most_specialized_impl(&vec_local),
+            Some(impl_of("Foo", 0)[0])
+        );
+        assert_eq!(
+            foo.most_specialized_impl(&vec_other),
+            Some(impl_of("Foo", 1)[0])
+        );
+        assert_eq!(foo.most_specialized_impl(&other), None);
+
+        let bar = CoherenceSolver::new(
+            program,
+            SolverChoice::default(),
+            program.trait_ids[&intern("Bar")],
+        );
+        let local = trait_ref("Bar", ty("Local", vec![]));
+        assert_eq!(
+            bar.most_specialized_impl(&local),
+            Some(impl_of("Bar", 0)[0])
+        );
+        assert_eq!(
+            bar.most_specialized_impl(&trait_ref("Bar", ty("Other", vec![]))),
+            Some(impl_of("Bar", 1)[0])
+        );
+
+        // The two marker impls apply equally, so neither is chosen.
+        let marker = CoherenceSolver::new(
+            program,
+            SolverChoice::default(),
+            program.trait_ids[&intern("Marker")],
+        );
+        assert_eq!(
+            marker.most_specialized_impl(&trait_ref("Marker", ty("Local", vec![]))),
+            None
+        );
+    });
+}
+
+#[test]
+fn type_name_is_local() {
+    let db = ChalkDatabase::with(
+        "
+            #[upstream] trait Remote { type Assoc; }
+            #[upstream] struct Vec<T> { }
+            trait Local { type Assoc; }
+            struct Foo { }
+            ",
+        SolverChoice::default(),
+    );
+    db.with_program(|program| {
+        let is_local = |name: TypeName<ChalkIr>| name.is_local(&db);
+        let assoc = |trait_name: &str| {
+            let trait_id = program.trait_ids[&intern(trait_name)];
+            program.trait_data[&trait_id].associated_ty_ids[0]
+        };
+
+        assert!(is_local(TypeName::Struct(
+            program.struct_ids[&intern("Foo")]
+        )));
+        assert!(!is_local(TypeName::Struct(
+            program.struct_ids[&intern("Vec")]
+        )));
+        assert!(is_local(TypeName::AssociatedType(assoc("Local"))));
+        assert!(!is_local(TypeName::AssociatedType(assoc("Remote"))));
+        assert!(!is_local(TypeName::Error));
+    });
+}
@@ -1,6 +1,7 @@
 //! Tests targeting coinduction specifically
 
 use super::*;
+use lalrpop_intern::intern;
 
 #[test]
 fn mixed_semantics() {
@@ -441,3 +442,22 @@ fn coinductive_multicycle4() {
         }
     }
 }
+
+#[test]
+fn requires_coinduction() {
+    let db = ChalkDatabase::with(
+        "
+            #[auto] trait Send { }
+            #[coinductive] trait Foo { }
+            trait Bar { }
+        ",
+        SolverChoice::default(),
+    );
+    let requires = |name: &str| {
+        let trait_id = db.trait_datum_by_name(&intern(name)).unwrap().id;
+        chalk_solve::requires_coinduction(trait_id, &db)
+    };
+    assert!(requires("Send"));
+    assert!(requires("Foo"));
+    assert!(!requires("Bar"));
+}
@@ -1,6 +1,10 @@
 //! Tests related to the implied bounds rules.
 
 use super::*;
+use chalk_ir::{ApplicationTy, Substitution, TraitRef, TypeName, WhereClause};
+use chalk_solve::elaborate::ElaborateBound;
+use chalk_solve::ext::ImplDatumExt;
+use lalrpop_intern::intern;
 
 #[test]
 fn implied_bounds() {
@@ -157,3 +161,63 @@ fn higher_ranked_implied_bounds() {
         }
     }
 }
+
+#[test]
+fn elaborate_bound() {
+    let db = ChalkDatabase::with(
+        "
+            trait Clone { }
+            trait Iterator where Self: Clone { }
+            trait DoubleEndedIterator where Self: Iterator, Self: Clone { }
+            struct u32 { }
+        ",
+        SolverChoice::default(),
+    );
+    db.with_program(|program| {
+        let interner = &ChalkIr;
+        let u32 = ApplicationTy {
+            name: TypeName::Struct(program.struct_ids[&intern("u32")]),
+            substitution: Substitution::empty(interner),
+        }
+        .intern(interner);
+        let where_clause = WhereClause::Implemented(TraitRef {
+            trait_id: program.trait_ids[&intern("DoubleEndedIterator")],
+            substitution: Substitution::from1(interner, u32),
+        });
+
+        let elaborated = ElaborateBound::<ChalkIr>::new(&db).elaborate(vec![where_clause]);
+        assert_eq!(
+            format!("{:?}", elaborated),
+            "[Implemented(u32: DoubleEndedIterator), \
+             Implemented(u32: Iterator), \
+             Implemented(u32: Clone)]"
+        );
+    });
+}
+
+#[test]
+fn impl_env_clauses() {
+    let db = ChalkDatabase::with(
+        "
+            trait Clone { }
+            trait Copy where Self: Clone { }
+            struct Vec<T> { }
+
+            impl<T> Clone for Vec<T> { }
+            impl<T> Copy for Vec<T> { }
+        ",
+        SolverChoice::default(),
+    );
+    db.with_program(|program| {
+        let copy = program.trait_ids[&intern("Copy")];
+        let impl_datum = program
+            .impl_data
+            .values()
+            .find(|impl_datum| impl_datum.trait_id() == copy)
+            .unwrap();
+        assert_eq!(
+            format!("{:?}", impl_datum.env_clauses(&db)),
+            "[for<type> FromEnv(Vec<^0.0>: Clone) :- FromEnv(Vec<^0.0>: Copy)]"
+        );
+    });
+}
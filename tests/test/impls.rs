@@ -639,3 +639,56 @@ fn clauses_in_if_goals() {
         }
     }
 }
+
+#[test]
+fn struct_sized() {
+    test! {
+        program {
+            #[lang(sized)] trait Sized { }
+
+            struct Empty { }
+            struct Wrapper<T> { t: T }
+            struct Pair<T, U> where T: Sized { t: T, u: U }
+        }
+
+        goal {
+            Empty: Sized
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            forall<T> { Wrapper<T>: Sized }
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            forall<T> {
+                if (T: Sized) {
+                    Wrapper<T>: Sized
+                }
+            }
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            Wrapper<Wrapper<Empty>>: Sized
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            forall<T> { Pair<Empty, Wrapper<T>>: Sized }
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            forall<T> { Pair<T, Empty>: Sized }
+        } yields {
+            "Unique"
+        }
+    }
+}
@@ -507,3 +507,202 @@ fn non_enumerable_traits_reorder() {
         }
     }
 }
+
+#[test]
+fn solver_prove_all_any() {
+    let db = ChalkDatabase::with(
+        "
+            trait Foo { }
+            struct Bar { }
+            struct Baz { }
+            struct Qux { }
+            impl Foo for Bar { }
+            impl Foo for Qux { }
+            ",
+        SolverChoice::default(),
+    );
+    let goal = |text: &str| {
+        db.parse_and_lower_goal(text)
+            .unwrap()
+            .into_closed_goal(db.interner())
+    };
+    let proven = goal("Bar: Foo");
+    let unproven = goal("Baz: Foo");
+    // Peeling the `exists` leaves `T` free in the canonical goal, and
+    // both `Bar` and `Qux` could be `T`.
+    let ambiguous = db
+        .parse_and_lower_goal("exists<T> { T: Foo }")
+        .unwrap()
+        .into_peeled_goal(db.interner());
+    assert_eq!(ambiguous.canonical.binders.len(), 1);
+
+    let solver = db.solver();
+    let mut solver = solver.lock().unwrap();
+    assert!(solver.prove_all(&db, std::slice::from_ref(&proven)));
+    assert!(!solver.prove_all(&db, std::slice::from_ref(&ambiguous)));
+    let mixed = [unproven, proven];
+    assert!(!solver.prove_all(&db, &mixed));
+    assert!(solver.prove_any(&db, &mixed));
+    let [unproven, _] = mixed;
+    assert!(!solver.prove_any(&db, &[unproven, ambiguous]));
+    assert!(solver.prove_all(&db, &[]));
+    assert!(!solver.prove_any(&db, &[]));
+}
+
+#[test]
+fn solver_initial_clauses() {
+    use chalk_ir::{
+        ApplicationTy, DomainGoal, Goals, ProgramClauseData, ProgramClauseImplication,
+        Substitution, TraitRef, TyData, TypeName, WhereClause,
+    };
+    use lalrpop_intern::intern;
+
+    let db = ChalkDatabase::with(
+        "
+            trait Foo { }
+            struct Bar { }
+            struct Baz { }
+            ",
+        SolverChoice::default(),
+    );
+    let interner = db.interner();
+    let program = db.checked_program().unwrap();
+
+    // Seed the solver with the fact `Implemented(Bar: Foo)`, for
+    // which the program itself has no impl.
+    let bar = TyData::Apply(ApplicationTy {
+        name: TypeName::Struct(program.struct_ids[&intern("Bar")]),
+        substitution: Substitution::empty(interner),
+    })
+    .intern(interner);
+    let trait_ref = TraitRef {
+        trait_id: program.trait_ids[&intern("Foo")],
+        substitution: Substitution::from(interner, vec![bar]),
+    };
+    let clause = ProgramClauseData::Implies(ProgramClauseImplication {
+        consequence: DomainGoal::Holds(WhereClause::Implemented(trait_ref)),
+        conditions: Goals::new(interner),
+    })
+    .intern(interner);
+
+    let goal = |text: &str| {
+        db.parse_and_lower_goal(text)
+            .unwrap()
+            .into_closed_goal(interner)
+    };
+    chalk_ir::tls::set_current_program(&program, || {
+        let mut seeded = SolverChoice::default().into_solver_with_initial_clauses(vec![clause]);
+        assert_result(&seeded.solve(&db, &goal("Bar: Foo")), "Unique");
+        assert_result(
+            &seeded.solve(&db, &goal("Baz: Foo")),
+            "No possible solution",
+        );
+
+        let mut unseeded = SolverChoice::default().into_solver();
+        assert_result(
+            &unseeded.solve(&db, &goal("Bar: Foo")),
+            "No possible solution",
+        );
+    });
+}
+
+#[test]
+fn builtin_impls_for_is_partial() {
+    use chalk_ir::{ApplicationTy, Substitution, TyData, TypeName};
+    use chalk_rust_ir::WellKnownTrait;
+    use chalk_solve::clauses::builtin_impls_for;
+    use lalrpop_intern::intern;
+
+    let db = ChalkDatabase::with(
+        "
+            #[lang(sized)] trait Sized { }
+            struct Unit { }
+            struct Wrapper { field: Unit }
+            ",
+        SolverChoice::default(),
+    );
+    let interner = db.interner();
+    let program = db.checked_program().unwrap();
+    let struct_ty = |name: &str| {
+        TyData::Apply(ApplicationTy {
+            name: TypeName::Struct(program.struct_ids[&intern(name)]),
+            substitution: Substitution::empty(interner),
+        })
+        .intern(interner)
+    };
+
+    assert_eq!(
+        builtin_impls_for(&struct_ty("Unit"), &db),
+        vec![WellKnownTrait::SizedTrait]
+    );
+
+    // `Wrapper` is `Sized` because its field is, but that is only
+    // found by a query.
+    assert_eq!(builtin_impls_for(&struct_ty("Wrapper"), &db), vec![]);
+    chalk_ir::tls::set_current_program(&program, || {
+        let goal = |text: &str| {
+            db.parse_and_lower_goal(text)
+                .unwrap()
+                .into_closed_goal(interner)
+        };
+        assert_result(&db.solve(&goal("Wrapper: Sized")), "Unique");
+    });
+}
+
+#[test]
+fn relevant_impls() {
+    use chalk_ir::{ApplicationTy, Substitution, TraitRef, Ty, TyData, TypeName};
+    use chalk_solve::clauses::relevant_impls;
+    use lalrpop_intern::intern;
+
+    let db = ChalkDatabase::with(
+        "
+            trait Foo { }
+            struct A { }
+            struct B { }
+            struct Vec<T> { }
+            impl Foo for A { }
+            impl<T> Foo for Vec<T> where T: Foo { }
+            impl !Foo for B { }
+            ",
+        SolverChoice::default(),
+    );
+    let interner = db.interner();
+    let program = db.checked_program().unwrap();
+    let struct_ty = |name: &str, parameters: Vec<Ty<ChalkIr>>| {
+        TyData::Apply(ApplicationTy {
+            name: TypeName::Struct(program.struct_ids[&intern(name)]),
+            substitution: Substitution::from(interner, parameters),
+        })
+        .intern(interner)
+    };
+    // The self type of each impl relevant to `ty: Foo`.
+    let relevant = |ty: Ty<ChalkIr>| {
+        let trait_ref = TraitRef {
+            trait_id: program.trait_ids[&intern("Foo")],
+            substitution: Substitution::from(interner, vec![ty]),
+        };
+        relevant_impls(&trait_ref, &db)
+            .into_iter()
+            .map(|impl_id| {
+                let trait_ref = &program.impl_data[&impl_id].binders.value.trait_ref;
+                match trait_ref.self_type_parameter(interner).data(interner) {
+                    TyData::Apply(ApplicationTy {
+                        name: TypeName::Struct(id),
+                        ..
+                    }) => *id,
+                    ty => panic!("unexpected self type: {:?}", ty),
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let struct_id = |name: &str| program.struct_ids[&intern(name)];
+    assert_eq!(relevant(struct_ty("A", vec![])), vec![struct_id("A")]);
+    // Only the impl headers are considered, so the `Vec` impl is
+    // relevant even though `B: Foo` does not hold.
+    let vec_b = struct_ty("Vec", vec![struct_ty("B", vec![])]);
+    assert_eq!(relevant(vec_b), vec![struct_id("Vec")]);
+    // Negative impls are never relevant.
+    assert_eq!(relevant(struct_ty("B", vec![])), vec![]);
+}
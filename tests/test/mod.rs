@@ -209,10 +209,13 @@ fn solve_goal(program_text: &str, goals: Vec<(&str, SolverChoice, TestGoal)>) {
             println!("goal {}", goal_text);
             assert!(goal_text.starts_with("{"));
             assert!(goal_text.ends_with("}"));
-            let goal = chalk_parse::parse_goal(&goal_text[1..goal_text.len() - 1])
-                .unwrap()
-                .lower(&*program)
-                .unwrap();
+            let goal = match chalk_parse::parse_goal(&goal_text[1..goal_text.len() - 1]) {
+                Ok(goal) => goal.lower(&*program).unwrap(),
+                Err(e) => panic!(
+                    "goal failed to parse at {}:{}: got `{}`, expected one of {:?}",
+                    e.line, e.column, e.got, e.expected
+                ),
+            };
 
             println!("using solver: {:?}", solver_choice);
             let peeled_goal = goal.into_peeled_goal(db.interner());
@@ -1,6 +1,12 @@
 //! Tests related to projection of associated types and normalization.
 
 use super::*;
+use chalk_ir::{
+    AliasEq, AliasTy, ApplicationTy, BoundVar, DebruijnIndex, ProjectionTy, Substitution, Ty,
+    TyData, TypeName, WhereClause,
+};
+use chalk_solve::normalize::{eagerly_normalize, lift_projections};
+use lalrpop_intern::intern;
 
 #[test]
 fn normalize_basic() {
@@ -737,3 +743,113 @@ fn issue_144_regression() {
         }
     }
 }
+
+#[test]
+fn eagerly_normalize_closed_projection() {
+    let db = ChalkDatabase::with(
+        "
+            trait Iterator { type Item; }
+            struct Vec<T> { }
+            struct Box<T> { }
+            struct u32 { }
+            impl<T> Iterator for Vec<T> {
+                type Item = Box<T>;
+            }
+        ",
+        SolverChoice::default(),
+    );
+    db.with_program(|program| {
+        let interner = &ChalkIr;
+        let apply = |name: &str, args: Vec<Ty<ChalkIr>>| {
+            ApplicationTy {
+                name: TypeName::Struct(program.struct_ids[&intern(name)]),
+                substitution: Substitution::from(interner, args),
+            }
+            .intern(interner)
+        };
+        let item = |self_ty: Ty<ChalkIr>| {
+            ProjectionTy {
+                associated_ty_id: *program.associated_ty_data.keys().next().unwrap(),
+                substitution: Substitution::from1(interner, self_ty),
+            }
+            .intern(interner)
+        };
+        let normalize = |ty: &Ty<ChalkIr>| eagerly_normalize::<ChalkIr, _>(ty, &db);
+        let u32 = apply("u32", vec![]);
+
+        // `Vec<<Vec<u32> as Iterator>::Item>` normalizes to `Vec<Box<u32>>`.
+        let ty = apply("Vec", vec![item(apply("Vec", vec![u32.clone()]))]);
+        assert_eq!(
+            normalize(&ty),
+            apply("Vec", vec![apply("Box", vec![u32.clone()])])
+        );
+
+        // `<Vec<T> as Iterator>::Item` mentions a free variable, and
+        // `<u32 as Iterator>::Item` has no impl, so neither changes.
+        let t = BoundVar::new(DebruijnIndex::INNERMOST, 0).to_ty(interner);
+        let generic = item(apply("Vec", vec![t]));
+        assert_eq!(normalize(&generic), generic);
+        let missing = item(u32);
+        assert_eq!(normalize(&missing), missing);
+    });
+}
+
+#[test]
+fn lift_projections_to_variables() {
+    let db = ChalkDatabase::with(
+        "
+            trait Iterator { type Item; }
+            struct Vec<T> { }
+            struct u32 { }
+        ",
+        SolverChoice::default(),
+    );
+    db.with_program(|program| {
+        let interner = &ChalkIr;
+        let apply = |name: &str, args: Vec<Ty<ChalkIr>>| {
+            ApplicationTy {
+                name: TypeName::Struct(program.struct_ids[&intern(name)]),
+                substitution: Substitution::from(interner, args),
+            }
+            .intern(interner)
+        };
+        let item = |self_ty: Ty<ChalkIr>| {
+            AliasTy::Projection(ProjectionTy {
+                associated_ty_id: *program.associated_ty_data.keys().next().unwrap(),
+                substitution: Substitution::from1(interner, self_ty),
+            })
+        };
+        let var = |index| BoundVar::new(DebruijnIndex::INNERMOST, index).to_ty(interner);
+        let u32 = apply("u32", vec![]);
+
+        // Each projection becomes the variable of its constraint, in
+        // the order they are encountered.
+        let ty = apply(
+            "Vec",
+            vec![
+                TyData::Alias(item(u32.clone())).intern(interner),
+                TyData::Alias(item(apply("Vec", vec![u32.clone()]))).intern(interner),
+            ],
+        );
+        let (lifted, constraints) = lift_projections(&ty, interner);
+        assert_eq!(lifted, apply("Vec", vec![var(0), var(1)]));
+        assert_eq!(
+            constraints,
+            vec![
+                WhereClause::AliasEq(AliasEq {
+                    alias: item(u32.clone()),
+                    ty: var(0),
+                }),
+                WhereClause::AliasEq(AliasEq {
+                    alias: item(apply("Vec", vec![u32.clone()])),
+                    ty: var(1),
+                }),
+            ]
+        );
+
+        // A type without projections is only shifted in.
+        let (lifted, constraints) = lift_projections(&u32, interner);
+        assert_eq!(lifted, u32);
+        assert!(constraints.is_empty());
+    });
+}
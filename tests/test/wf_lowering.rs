@@ -1,4 +1,10 @@
+use chalk_integration::db::ChalkDatabase;
 use chalk_integration::query::LoweringDatabase;
+use chalk_ir::interner::ChalkIr;
+use chalk_ir::Goal;
+use chalk_solve::ext::{GoalExt, ImplDatumExt};
+use chalk_solve::wf::WfSolver;
+use chalk_solve::SolverChoice;
 
 #[test]
 fn well_formed_trait_decl() {
@@ -710,3 +716,236 @@ fn struct_sized_constraints() {
         }
     }
 }
+
+#[test]
+fn impl_full_wf_goals() {
+    let db = ChalkDatabase::with(
+        "
+            trait Clone { }
+            trait Foo {
+                type Item<T>: Clone;
+            }
+
+            struct Bar<T> { }
+            struct Baz { }
+
+            impl Foo for Baz {
+                type Item<T> = Bar<T>;
+            }
+        ",
+        SolverChoice::default(),
+    );
+
+    // The program fails the WF check, so look at the unchecked IR.
+    let program = db.program_ir().unwrap();
+    let impl_datum = program.impl_data.values().next().unwrap();
+    let goals = impl_datum.full_wf_goals(&db);
+    assert_eq!(goals.len(), 2);
+
+    // The impl header is fine, but `Bar<T>: Clone` does not hold for
+    // the associated type value.
+    let solve = |goal: &Goal<ChalkIr>| db.solve(&goal.clone().into_closed_goal(&ChalkIr));
+    assert!(solve(&goals[0]).unwrap().is_unique());
+    assert!(solve(&goals[1]).is_none());
+}
+
+#[test]
+fn well_formed_impl_and_struct_goals() {
+    use chalk_ir::cast::Cast;
+    use chalk_ir::{DomainGoal, TyData, TypeName, WellFormed};
+    use lalrpop_intern::intern;
+
+    let db = ChalkDatabase::with(
+        "
+            trait Clone { }
+            trait Copy where Self: Clone { }
+            struct A { }
+            struct B { }
+            struct Wrapper<T> where T: Clone { }
+            struct Good { field: Wrapper<A> }
+            struct Bad<T> { field: Wrapper<T> }
+
+            impl Clone for A { }
+            impl Copy for A { }
+            impl Copy for B { }
+        ",
+        SolverChoice::default(),
+    );
+
+    // The program fails the WF check, so look at the unchecked IR.
+    let program = db.program_ir().unwrap();
+    let solve = |goal: WellFormed<ChalkIr>| {
+        let goal: Goal<ChalkIr> = DomainGoal::WellFormed(goal).cast(&ChalkIr);
+        db.solve(&goal.into_closed_goal(&ChalkIr))
+    };
+    chalk_ir::tls::set_current_program(&program, || {
+        let good = program.struct_ids[&intern("Good")];
+        assert!(solve(WellFormed::Struct(good)).unwrap().is_unique());
+        let bad = program.struct_ids[&intern("Bad")];
+        assert!(solve(WellFormed::Struct(bad)).is_none());
+
+        // `B: Copy` requires `B: Clone`, which does not hold.
+        let b = TypeName::Struct(program.struct_ids[&intern("B")]);
+        for (&impl_id, impl_datum) in &program.impl_data {
+            let self_ty = impl_datum
+                .binders
+                .value
+                .trait_ref
+                .self_type_parameter(&ChalkIr);
+            match self_ty.data(&ChalkIr) {
+                TyData::Apply(apply) if apply.name == b => {
+                    assert!(solve(WellFormed::Impl(impl_id)).is_none())
+                }
+                _ => assert!(solve(WellFormed::Impl(impl_id)).unwrap().is_unique()),
+            }
+        }
+    });
+}
+
+#[test]
+fn check_associated_ty_value() {
+    let check = |program_text: &str| {
+        let db = ChalkDatabase::with(program_text, SolverChoice::default());
+        let program = db.program_ir().unwrap();
+        let id = *program.associated_ty_values.keys().next().unwrap();
+        chalk_ir::tls::set_current_program(&program, || {
+            WfSolver::<ChalkIr>::new(&db, SolverChoice::default())
+                .check_associated_ty_value(id)
+                .map_err(|e| e.to_string())
+        })
+    };
+
+    assert_eq!(
+        check(
+            "
+            trait Clone { }
+            trait Foo { type Item: Clone; }
+            struct A { }
+            impl Clone for A { }
+            impl Foo for A { type Item = A; }
+            "
+        ),
+        Ok(())
+    );
+
+    assert_eq!(
+        check(
+            "
+            trait Clone { }
+            trait Foo { type Item: Clone; }
+            struct A { }
+            impl Foo for A { type Item = A; }
+            "
+        ),
+        Err(
+            "value of associated type `(Foo::Item)` does not meet well-formedness requirements"
+                .to_string()
+        )
+    );
+}
+
+#[test]
+fn associated_const_values() {
+    lowering_success! {
+        program {
+            struct Usize { }
+            struct Foo { }
+
+            trait Bounded {
+                const MAX: Usize;
+                const MIN: Usize = _;
+            }
+
+            impl Bounded for Foo {
+                const MAX: Usize = _;
+            }
+        }
+    }
+
+    let db = ChalkDatabase::with(
+        "
+            struct Usize { }
+            struct Foo { }
+
+            trait Bounded {
+                const MAX: Usize;
+                const MIN: Usize = _;
+            }
+
+            impl Bounded for Foo {
+                const MIN: Usize = _;
+            }
+        ",
+        SolverChoice::default(),
+    );
+    let error = db.checked_program().unwrap_err().to_string();
+    assert!(
+        error.starts_with("trait impl for `Bounded` does not provide a value for associated const"),
+        "{}",
+        error
+    );
+    assert!(error.contains("MAX"), "{}", error);
+}
+
+#[test]
+fn associated_const_types() {
+    lowering_success! {
+        program {
+            struct Usize { }
+            struct Foo { }
+
+            trait Bounded<T> {
+                type Item;
+                const MAX: T;
+                type Other;
+            }
+
+            impl Bounded<Usize> for Foo {
+                type Item = Foo;
+                const MAX: Usize = _;
+                type Other = Foo;
+            }
+        }
+    }
+
+    // The declared type is compared up to normalization.
+    lowering_success! {
+        program {
+            struct Usize { }
+            struct Foo { }
+
+            trait Bounded {
+                type Item;
+                const MAX: <Self as Bounded>::Item;
+            }
+
+            impl Bounded for Foo {
+                type Item = Usize;
+                const MAX: Usize = _;
+            }
+        }
+    }
+
+    let db = ChalkDatabase::with(
+        "
+            struct Usize { }
+            struct Foo { }
+
+            trait Bounded<T> {
+                const MAX: T;
+            }
+
+            impl Bounded<Usize> for Foo {
+                const MAX: Foo = _;
+            }
+        ",
+        SolverChoice::default(),
+    );
+    let error = db.checked_program().unwrap_err().to_string();
+    assert!(
+        error.starts_with("trait impl for `Bounded` gives associated const"),
+        "{}",
+        error
+    );
+    assert!(error.contains("MAX"), "{}", error);
+}